@@ -0,0 +1,194 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use skipjack::skipjack::{decrypt_block, encrypt_block, rules, Cipher};
+
+const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+fn bench_encrypt_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_block");
+
+    // The free function looks up each round's key bytes in the secret key.
+    group.bench_function("free function", |b| {
+        b.iter(|| encrypt_block(black_box(0x33221100ddccbbaa), black_box(KEY)))
+    });
+
+    // `Cipher` precomputes them once, when it's created.
+    let cipher = Cipher::new(KEY);
+    group.bench_function("Cipher", |b| {
+        b.iter(|| black_box(&cipher).encrypt_block(black_box(0x33221100ddccbbaa)))
+    });
+
+    group.finish();
+}
+
+fn bench_decrypt_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decrypt_block");
+
+    group.bench_function("free function", |b| {
+        b.iter(|| decrypt_block(black_box(0x2587cae27a12d300), black_box(KEY)))
+    });
+
+    let cipher = Cipher::new(KEY);
+    group.bench_function("Cipher", |b| {
+        b.iter(|| black_box(&cipher).decrypt_block(black_box(0x2587cae27a12d300)))
+    });
+
+    group.finish();
+}
+
+fn bench_rules(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rules");
+
+    // A single round of each rule. Any restructuring of the rules (see the notes
+    // in `lib.rs`) should show up here first.
+    group.bench_function("rule_a", |b| {
+        b.iter(|| {
+            let mut words = black_box([0x3322, 0x1100, 0xddcc, 0xbbaa]);
+            rules::rule_a(&mut words, &mut black_box(1), black_box(&KEY));
+            words
+        })
+    });
+
+    group.bench_function("rule_b", |b| {
+        b.iter(|| {
+            let mut words = black_box([0x3322, 0x1100, 0xddcc, 0xbbaa]);
+            rules::rule_b(&mut words, &mut black_box(9), black_box(&KEY));
+            words
+        })
+    });
+
+    group.finish();
+}
+
+// The two ways of writing the state update in rules A and B that the note in
+// `lib.rs` compares. Both are local copies of the crate's rules, so that the
+// comparison can be re-run as the crate changes; they share one rule G, and
+// differ only in how they update the state.
+#[rustfmt::skip]
+const F: [u8; 256] = [
+    0xa3, 0xd7, 0x09, 0x83, 0xf8, 0x48, 0xf6, 0xf4, 0xb3, 0x21, 0x15, 0x78, 0x99, 0xb1, 0xaf, 0xf9,
+    0xe7, 0x2d, 0x4d, 0x8a, 0xce, 0x4c, 0xca, 0x2e, 0x52, 0x95, 0xd9, 0x1e, 0x4e, 0x38, 0x44, 0x28,
+    0x0a, 0xdf, 0x02, 0xa0, 0x17, 0xf1, 0x60, 0x68, 0x12, 0xb7, 0x7a, 0xc3, 0xe9, 0xfa, 0x3d, 0x53,
+    0x96, 0x84, 0x6b, 0xba, 0xf2, 0x63, 0x9a, 0x19, 0x7c, 0xae, 0xe5, 0xf5, 0xf7, 0x16, 0x6a, 0xa2,
+    0x39, 0xb6, 0x7b, 0x0f, 0xc1, 0x93, 0x81, 0x1b, 0xee, 0xb4, 0x1a, 0xea, 0xd0, 0x91, 0x2f, 0xb8,
+    0x55, 0xb9, 0xda, 0x85, 0x3f, 0x41, 0xbf, 0xe0, 0x5a, 0x58, 0x80, 0x5f, 0x66, 0x0b, 0xd8, 0x90,
+    0x35, 0xd5, 0xc0, 0xa7, 0x33, 0x06, 0x65, 0x69, 0x45, 0x00, 0x94, 0x56, 0x6d, 0x98, 0x9b, 0x76,
+    0x97, 0xfc, 0xb2, 0xc2, 0xb0, 0xfe, 0xdb, 0x20, 0xe1, 0xeb, 0xd6, 0xe4, 0xdd, 0x47, 0x4a, 0x1d,
+    0x42, 0xed, 0x9e, 0x6e, 0x49, 0x3c, 0xcd, 0x43, 0x27, 0xd2, 0x07, 0xd4, 0xde, 0xc7, 0x67, 0x18,
+    0x89, 0xcb, 0x30, 0x1f, 0x8d, 0xc6, 0x8f, 0xaa, 0xc8, 0x74, 0xdc, 0xc9, 0x5d, 0x5c, 0x31, 0xa4,
+    0x70, 0x88, 0x61, 0x2c, 0x9f, 0x0d, 0x2b, 0x87, 0x50, 0x82, 0x54, 0x64, 0x26, 0x7d, 0x03, 0x40,
+    0x34, 0x4b, 0x1c, 0x73, 0xd1, 0xc4, 0xfd, 0x3b, 0xcc, 0xfb, 0x7f, 0xab, 0xe6, 0x3e, 0x5b, 0xa5,
+    0xad, 0x04, 0x23, 0x9c, 0x14, 0x51, 0x22, 0xf0, 0x29, 0x79, 0x71, 0x7e, 0xff, 0x8c, 0x0e, 0xe2,
+    0x0c, 0xef, 0xbc, 0x72, 0x75, 0x6f, 0x37, 0xa1, 0xec, 0xd3, 0x8e, 0x62, 0x8b, 0x86, 0x10, 0xe8,
+    0x08, 0x77, 0x11, 0xbe, 0x92, 0x4f, 0x24, 0xc5, 0x32, 0x36, 0x9d, 0xcf, 0xf3, 0xa6, 0xbb, 0xac,
+    0x5e, 0x6c, 0xa9, 0x13, 0x57, 0x25, 0xb5, 0xe3, 0xbd, 0xa8, 0x3a, 0x01, 0x05, 0x59, 0x2a, 0x46,
+];
+
+fn rule_g(word: u16, step: u16, key: &[u8; 10]) -> u16 {
+    let [mut g1, mut g2] = word.to_be_bytes();
+    for i in 0..4 {
+        let next = F[(g2 ^ key[(4 * step as usize + i) % 10]) as usize] ^ g1;
+        g1 = g2;
+        g2 = next;
+    }
+
+    u16::from_be_bytes([g1, g2])
+}
+
+// As the crate does it: copy the words, then assign into them.
+fn encrypt_copy(words: &mut [u16; 4], key: &[u8; 10]) {
+    for counter in 1..=32u16 {
+        let original_words = *words;
+        let g = rule_g(original_words[0], counter - 1, key);
+        if (counter - 1) / 8 % 2 == 0 {
+            words[0] = g ^ original_words[3] ^ counter;
+            words[1] = g;
+            words[2] = original_words[1];
+            words[3] = original_words[2];
+        } else {
+            words[0] = original_words[3];
+            words[1] = g;
+            words[2] = original_words[0] ^ original_words[1] ^ counter;
+            words[3] = original_words[2];
+        }
+    }
+}
+
+// Without the copy: build the new state as a fresh array.
+fn encrypt_fresh(words: &mut [u16; 4], key: &[u8; 10]) {
+    for counter in 1..=32u16 {
+        let [w1, w2, w3, w4] = *words;
+        let g = rule_g(w1, counter - 1, key);
+        *words = if (counter - 1) / 8 % 2 == 0 {
+            [g ^ w4 ^ counter, g, w2, w3]
+        } else {
+            [w4, g, w1 ^ w2 ^ counter, w3]
+        };
+    }
+}
+
+fn bench_rule_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rule_state");
+
+    // Both variants must still be Skipjack, or the comparison means nothing.
+    for encrypt in [encrypt_copy, encrypt_fresh].iter() {
+        let mut words = [0x3322, 0x1100, 0xddcc, 0xbbaa];
+        encrypt(&mut words, &KEY);
+        assert_eq!(words, [0x2587, 0xcae2, 0x7a12, 0xd300]);
+    }
+
+    group.bench_function("copy + assign", |b| {
+        b.iter(|| {
+            let mut words = black_box([0x3322, 0x1100, 0xddcc, 0xbbaa]);
+            encrypt_copy(&mut words, black_box(&KEY));
+            words
+        })
+    });
+
+    group.bench_function("fresh array", |b| {
+        b.iter(|| {
+            let mut words = black_box([0x3322, 0x1100, 0xddcc, 0xbbaa]);
+            encrypt_fresh(&mut words, black_box(&KEY));
+            words
+        })
+    });
+
+    group.finish();
+}
+
+fn bench_encrypt_blocks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encrypt_blocks");
+    group.throughput(Throughput::Bytes(8 * 1024));
+
+    let cipher = Cipher::new(KEY);
+    let plaintext: Vec<u64> = (0..1024u64)
+        .map(|i| i.wrapping_mul(0x9e3779b97f4a7c15))
+        .collect();
+
+    // One block at a time: each block's 32 rounds run back to back.
+    group.bench_function("loop", |b| {
+        let mut blocks = plaintext.clone();
+        b.iter(|| {
+            for block in blocks.iter_mut() {
+                *block = black_box(&cipher).encrypt_block(*block);
+            }
+        })
+    });
+
+    // Eight blocks at a time, with their rounds interleaved.
+    group.bench_function("interleaved", |b| {
+        let mut blocks = plaintext.clone();
+        b.iter(|| black_box(&cipher).encrypt_blocks_interleaved(black_box(&mut blocks)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_encrypt_block,
+    bench_decrypt_block,
+    bench_rules,
+    bench_rule_state,
+    bench_encrypt_blocks
+);
+criterion_main!(benches);
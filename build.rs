@@ -0,0 +1,42 @@
+// Generates Skipjack's key schedule as a lookup table.
+//
+// Each step of the cipher consumes four consecutive bytes of the 10-byte key,
+// wrapping around: step k uses bytes 4k, 4k+1, 4k+2, and 4k+3 (all mod 10).
+// Rather than computing these indices on every call to rule G, we compute
+// them once here and include the result in the crate.
+//
+// With the `ffi` feature, this also generates `skipjack.h`, a C header for the
+// crate's `extern "C"` functions, with cbindgen.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let mut table = String::from("pub(crate) const ROUND_KEY_INDEX: [[usize; 4]; 32] = [\n");
+
+    for step in 0..32 {
+        let indices: Vec<String> = (0..4).map(|i| ((4 * step + i) % 10).to_string()).collect();
+        table.push_str(&format!("    [{}],\n", indices.join(", ")));
+    }
+
+    table.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("round_key_index.rs"), table).unwrap();
+
+    // With the `ffi` feature, also generate a C header for the `extern "C"`
+    // functions (configured by `cbindgen.toml`).
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        cbindgen::generate(&crate_dir)
+            .expect("couldn't generate skipjack.h")
+            .write_to_file(Path::new(&out_dir).join("skipjack.h"));
+
+        println!("cargo:rerun-if-changed=src");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,281 @@
+//! A small command-line interface to Skipjack in codebook (ECB) mode.
+//!
+//! Usage: `skipjack (encrypt|decrypt) (--key HEX | --key-file PATH | --key-env VAR)`
+//!
+//! Blocks are read from standard input, one per line, as 16 hex digits, and the
+//! result for each is written to standard output in the same form. The key is
+//! hex, in any form accepted by `Key`'s `FromStr` implementation.
+//!
+//! Exactly one key source must be given. Prefer `--key-file` or `--key-env`:
+//! a key passed with `--key` is visible to other users in process listings, and
+//! ends up in your shell history.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process;
+
+use skipjack::skipjack::{decrypt_block, encrypt_block, Block, Direction, Key};
+
+const USAGE: &str =
+    "usage: skipjack (encrypt|decrypt) (--key HEX | --key-file PATH | --key-env VAR)";
+
+/// Where to read the key from.
+#[derive(Debug, PartialEq, Eq)]
+enum KeySource {
+    Arg(String),
+    File(String),
+    Env(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    direction: Direction,
+    key_source: KeySource,
+}
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Key(String),
+    Input(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}\n{}", msg, USAGE),
+            CliError::Key(msg) => write!(f, "bad key: {}", msg),
+            CliError::Input(msg) => write!(f, "bad input: {}", msg),
+            CliError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, CliError> {
+    let mut args = args.into_iter();
+
+    let direction = match args.next().as_deref() {
+        Some("encrypt") => Direction::Encrypt,
+        Some("decrypt") => Direction::Decrypt,
+        Some(other) => return Err(CliError::Usage(format!("unknown command: {}", other))),
+        None => return Err(CliError::Usage("missing command".into())),
+    };
+
+    let mut key_source = None;
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| CliError::Usage(format!("{} needs a value", flag)))?;
+        let source = match flag.as_str() {
+            "--key" => KeySource::Arg(value),
+            "--key-file" => KeySource::File(value),
+            "--key-env" => KeySource::Env(value),
+            _ => return Err(CliError::Usage(format!("unknown option: {}", flag))),
+        };
+
+        if key_source.replace(source).is_some() {
+            return Err(CliError::Usage(
+                "only one of --key, --key-file, and --key-env may be given".into(),
+            ));
+        }
+    }
+
+    let key_source = key_source.ok_or_else(|| {
+        CliError::Usage("one of --key, --key-file, or --key-env is required".into())
+    })?;
+
+    Ok(Args {
+        direction,
+        key_source,
+    })
+}
+
+// Loads and validates the key from its source. `var` looks up an environment
+// variable; it's a parameter so that tests don't have to modify the real
+// environment.
+fn load_key(source: &KeySource, var: impl Fn(&str) -> Option<String>) -> Result<Key, CliError> {
+    let hex = match source {
+        KeySource::Arg(hex) => hex.clone(),
+        KeySource::File(path) => fs::read_to_string(path)
+            .map_err(|e| CliError::Key(format!("couldn't read {}: {}", path, e)))?,
+        KeySource::Env(name) => {
+            var(name).ok_or_else(|| CliError::Key(format!("${} is not set", name)))?
+        }
+    };
+
+    // Parsing trims surrounding whitespace, so a trailing newline in a key
+    // file is fine.
+    hex.parse().map_err(|e| CliError::Key(format!("{}", e)))
+}
+
+fn run(args: &Args, key: Key, input: impl BufRead, mut output: impl Write) -> Result<(), CliError> {
+    for (number, line) in input.lines().enumerate() {
+        let line = line?;
+        let hex = line.trim();
+
+        let block: Block = hex.parse().map_err(|e| {
+            CliError::Input(format!(
+                "line {}: expected 16 hex digits ({})",
+                number + 1,
+                e
+            ))
+        })?;
+
+        let result = match args.direction {
+            Direction::Encrypt => encrypt_block(block, key.0),
+            Direction::Decrypt => decrypt_block(block, key.0),
+        };
+        writeln!(output, "{:016x}", result)?;
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(|args| {
+        let key = load_key(&args.key_source, |name| env::var(name).ok())?;
+
+        let stdin = io::stdin();
+        let stdout = io::stdout();
+        run(&args, key, stdin.lock(), stdout.lock())
+    });
+
+    if let Err(e) = result {
+        eprintln!("skipjack: {}", e);
+        process::exit(match e {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    fn args(args: &[&str]) -> Result<Args, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            args(&["encrypt", "--key-env", "SKIPJACK_KEY"]).unwrap(),
+            Args {
+                direction: Direction::Encrypt,
+                key_source: KeySource::Env("SKIPJACK_KEY".into()),
+            }
+        );
+        assert_eq!(
+            args(&["decrypt", "--key-file", "key.txt"]).unwrap(),
+            Args {
+                direction: Direction::Decrypt,
+                key_source: KeySource::File("key.txt".into()),
+            }
+        );
+
+        // Exactly one key source is required.
+        assert!(matches!(args(&["encrypt"]), Err(CliError::Usage(_))));
+        assert!(matches!(
+            args(&["encrypt", "--key-env", "A", "--key-file", "b"]),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            args(&["encrypt", "--key", "00", "--key", "00"]),
+            Err(CliError::Usage(_))
+        ));
+
+        assert!(matches!(args(&[]), Err(CliError::Usage(_))));
+        assert!(matches!(args(&["frobnicate"]), Err(CliError::Usage(_))));
+        assert!(matches!(
+            args(&["encrypt", "--key-file"]),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            args(&["encrypt", "--kee", "x"]),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_load_key() {
+        let path = env::temp_dir().join(format!("skipjack-cli-test-{}", process::id()));
+        fs::write(&path, "00:99:88:77:66:55:44:33:22:11\n").unwrap();
+
+        let env = |name: &str| match name {
+            "SKIPJACK_KEY" => Some("00998877665544332211".to_string()),
+            "BAD_KEY" => Some("0099887766".to_string()),
+            _ => None,
+        };
+
+        // Every source yields the same key.
+        let sources = [
+            KeySource::Arg("00 99 88 77 66 55 44 33 22 11".into()),
+            KeySource::File(path.to_str().unwrap().into()),
+            KeySource::Env("SKIPJACK_KEY".into()),
+        ];
+        for source in sources.iter() {
+            assert_eq!(load_key(source, env).unwrap(), Key(KEY));
+        }
+
+        // And every source is validated the same way.
+        assert!(matches!(
+            load_key(&KeySource::Env("BAD_KEY".into()), env),
+            Err(CliError::Key(_))
+        ));
+        fs::write(&path, "not a key").unwrap();
+        assert!(matches!(
+            load_key(&KeySource::File(path.to_str().unwrap().into()), env),
+            Err(CliError::Key(_))
+        ));
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            load_key(&KeySource::File(path.to_str().unwrap().into()), env),
+            Err(CliError::Key(_))
+        ));
+        assert!(matches!(
+            load_key(&KeySource::Env("SKIPJACK_KEY".into()), no_env),
+            Err(CliError::Key(_))
+        ));
+    }
+
+    #[test]
+    fn test_run() {
+        let args = args(&["encrypt", "--key", "00998877665544332211"]).unwrap();
+
+        let mut output = Vec::new();
+        run(
+            &args,
+            Key(KEY),
+            &b"33221100ddccbbaa\n0000000000000000\n"[..],
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("2587cae27a12d300"));
+        assert_eq!(lines.next(), Some("a5a459af7eba7e8c"));
+
+        assert!(matches!(
+            run(&args, Key(KEY), &b"33221100\n"[..], Vec::new()),
+            Err(CliError::Input(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Authenticated encryption, composed from CTR mode and CMAC.
+//!
+//! `seal` encrypts with CTR mode under one key and then authenticates the nonce
+//! and ciphertext with CMAC under another (encrypt-then-MAC). `open` checks the
+//! tag before decrypting anything, and returns only an error if it doesn't match.
+//!
+//! The encryption and MAC keys must be independent, and the usual CTR rule
+//! applies: never reuse a nonce with the same encryption key.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use super::{ctr, mac, util, Error};
+
+// Computes the tag over the nonce and ciphertext.
+fn tag(nonce: u64, ciphertext: &[u8], mac_key: impl Borrow<[u8; 10]>) -> [u8; 8] {
+    let mut input = Vec::with_capacity(8 + ciphertext.len());
+    input.extend_from_slice(&nonce.to_be_bytes());
+    input.extend_from_slice(ciphertext);
+
+    mac::cmac(&input, mac_key).to_be_bytes()
+}
+
+/// Encrypts and authenticates the given plaintext, returning the ciphertext
+/// with an 8-byte tag appended.
+///
+/// # Arguments
+///
+/// * `plaintext` - The data to encrypt
+/// * `nonce` - The CTR nonce, which must never be reused with `enc_key`
+/// * `enc_key` - The secret key to encrypt with
+/// * `mac_key` - The secret key to authenticate with
+pub fn seal(
+    plaintext: &[u8],
+    nonce: u64,
+    enc_key: impl Borrow<[u8; 10]>,
+    mac_key: impl Borrow<[u8; 10]>,
+) -> Vec<u8> {
+    let mut sealed = plaintext.to_vec();
+    ctr::apply_keystream(&mut sealed, nonce, enc_key);
+
+    let tag = tag(nonce, &sealed, mac_key);
+    sealed.extend_from_slice(&tag);
+
+    sealed
+}
+
+/// Verifies and decrypts the output of `seal`.
+///
+/// Returns `Error::AuthenticationFailed` if the tag doesn't match, in which
+/// case nothing is decrypted.
+///
+/// # Arguments
+///
+/// * `sealed` - The ciphertext, with its tag appended
+/// * `nonce` - The CTR nonce the data was sealed with
+/// * `enc_key` - The secret key to decrypt with
+/// * `mac_key` - The secret key to authenticate with
+pub fn open(
+    sealed: &[u8],
+    nonce: u64,
+    enc_key: impl Borrow<[u8; 10]>,
+    mac_key: impl Borrow<[u8; 10]>,
+) -> Result<Vec<u8>, Error> {
+    if sealed.len() < 8 {
+        return Err(Error::AuthenticationFailed);
+    }
+
+    let (ciphertext, received) = sealed.split_at(sealed.len() - 8);
+    if !util::ct_eq(&tag(nonce, ciphertext, mac_key), received) {
+        return Err(Error::AuthenticationFailed);
+    }
+
+    let mut plaintext = ciphertext.to_vec();
+    ctr::apply_keystream(&mut plaintext, nonce, enc_key);
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENC_KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const MAC_KEY: [u8; 10] = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0x00];
+
+    #[test]
+    fn test_roundtrip() {
+        let plaintext = b"attack at dawn";
+
+        let sealed = seal(plaintext, 1, ENC_KEY, MAC_KEY);
+        assert_eq!(sealed.len(), plaintext.len() + 8);
+
+        assert_eq!(open(&sealed, 1, ENC_KEY, MAC_KEY), Ok(plaintext.to_vec()));
+    }
+
+    #[test]
+    fn test_tampered() {
+        let sealed = seal(b"attack at dawn", 1, ENC_KEY, MAC_KEY);
+
+        // Flipping a single ciphertext bit is caught...
+        let mut tampered = sealed.clone();
+        tampered[0] ^= 0x01;
+        assert_eq!(
+            open(&tampered, 1, ENC_KEY, MAC_KEY),
+            Err(Error::AuthenticationFailed)
+        );
+
+        // ...as is a different nonce, a truncated tag, or the wrong MAC key.
+        assert_eq!(
+            open(&sealed, 2, ENC_KEY, MAC_KEY),
+            Err(Error::AuthenticationFailed)
+        );
+        assert_eq!(
+            open(&sealed[..4], 1, ENC_KEY, MAC_KEY),
+            Err(Error::AuthenticationFailed)
+        );
+        assert_eq!(
+            open(&sealed, 1, ENC_KEY, ENC_KEY),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Statistics for studying reduced-round Skipjack.
+//!
+//! With only a few rounds, most of the state passes through untouched or is
+//! merely XOR'ed around, so a change to the input has entirely predictable
+//! effects on the output. Each additional round mixes it further through rule
+//! G, until (well before round 32) the output looks unrelated to the input.
+//! The functions here measure that, for demonstrations.
+
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeSet;
+use core::borrow::Borrow;
+
+use super::util::SplitMix64;
+#[cfg(feature = "alloc")]
+use super::Cipher;
+use super::{block_to_words, encrypt_first_rounds, words_to_block, ROUNDS};
+
+// The input difference that `round_correlation` flips: the low bit of Word 4.
+const INPUT_DIFFERENCE: u64 = 1;
+
+// The output bit that `round_correlation` watches: also the low bit of Word 4.
+const OUTPUT_BIT: u64 = 1;
+
+// Encrypts a block with the first `rounds` rounds of Skipjack.
+fn encrypt_reduced(block: u64, key: &[u8; 10], rounds: usize) -> u64 {
+    let mut words = block_to_words(block);
+    encrypt_first_rounds(&mut words, key, rounds);
+
+    words_to_block(words)
+}
+
+/// Measures how predictably an input difference propagates through the first
+/// `rounds` rounds of Skipjack.
+///
+/// For `samples` pseudorandom plaintexts `x` (the same ones on every call), this
+/// encrypts both `x` and `x` with the low bit of Word 4 flipped, and checks
+/// whether the low bit of Word 4 differs between the two ciphertexts. The
+/// result is the bias of that bit: `|P(same) - P(different)|`, between 0 and 1.
+///
+/// A bias of 1 means the output difference is entirely determined by the input
+/// difference, as it is for the first few rounds. For an ideal cipher, the bias
+/// is 0, give or take sampling noise of about `1 / sqrt(samples)`; full Skipjack
+/// should be indistinguishable from that.
+///
+/// # Arguments
+///
+/// * `rounds` - The number of rounds to encrypt with
+/// * `samples` - The number of plaintext pairs to try
+/// * `key` - The secret key to encrypt with
+///
+/// # Panics
+///
+/// Panics if `rounds` is more than 32, or if `samples` is 0.
+pub fn round_correlation(rounds: usize, samples: usize, key: impl Borrow<[u8; 10]>) -> f64 {
+    assert!(rounds <= ROUNDS, "Skipjack has at most 32 rounds");
+    assert!(samples > 0, "at least one sample is required");
+
+    let key = key.borrow();
+    let mut rng = SplitMix64(0x5ca1ab1e);
+    let mut same = 0usize;
+    for _ in 0..samples {
+        let x = rng.next();
+        let a = encrypt_reduced(x, key, rounds);
+        let b = encrypt_reduced(x ^ INPUT_DIFFERENCE, key, rounds);
+
+        if (a ^ b) & OUTPUT_BIT == 0 {
+            same += 1;
+        }
+    }
+
+    let different = samples - same;
+    (same.max(different) - same.min(different)) as f64 / samples as f64
+}
+
+/// Statistics about the ciphertext of a corpus, as computed by `corpus_stats`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpusStats {
+    /// The number of distinct ciphertext blocks.
+    pub distinct_ciphertexts: usize,
+    /// The number of ciphertext blocks that repeat an earlier one.
+    pub collisions: usize,
+    /// How many times each byte value occurs in the ciphertext, indexed by byte.
+    pub byte_histogram: [u64; 256],
+}
+
+/// Encrypts every block of a corpus (in ECB mode), and returns statistics
+/// about the ciphertext.
+///
+/// Under a fixed key, Skipjack is a permutation, so two ciphertext blocks are
+/// equal exactly when their plaintext blocks are: `collisions` counts the
+/// duplicate plaintexts, which is ECB's weakness laid bare. The byte histogram
+/// of the ciphertext, on the other hand, should look uniform however skewed the
+/// plaintext was (give or take the duplicates).
+///
+/// # Arguments
+///
+/// * `blocks` - The corpus to encrypt
+/// * `key` - The secret key to encrypt with
+#[cfg(feature = "alloc")]
+pub fn corpus_stats(blocks: &[u64], key: impl Borrow<[u8; 10]>) -> CorpusStats {
+    let cipher = Cipher::new(key);
+    let mut ciphertexts = BTreeSet::new();
+    let mut byte_histogram = [0u64; 256];
+
+    for &block in blocks.iter() {
+        let ciphertext = cipher.encrypt_block(block);
+        ciphertexts.insert(ciphertext);
+
+        for &byte in ciphertext.to_be_bytes().iter() {
+            byte_histogram[byte as usize] += 1;
+        }
+    }
+
+    CorpusStats {
+        distinct_ciphertexts: ciphertexts.len(),
+        collisions: blocks.len() - ciphertexts.len(),
+        byte_histogram,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{encrypt_block, encrypt_block_rounds};
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_encrypt_reduced() {
+        assert_eq!(
+            encrypt_reduced(0x33221100ddccbbaa, &KEY, 32),
+            0x2587cae27a12d300
+        );
+        assert_eq!(
+            encrypt_reduced(0x33221100ddccbbaa, &KEY, 5),
+            encrypt_block_rounds::<5>(0x33221100ddccbbaa, KEY)
+        );
+        assert_eq!(encrypt_reduced(7, &KEY, 32), encrypt_block(7u64, KEY));
+    }
+
+    #[test]
+    fn test_round_correlation() {
+        // The low bit of Word 4 takes three rounds to reach rule G, so for the
+        // first few rounds, the output difference is completely predictable.
+        for rounds in 0..=3 {
+            assert_eq!(round_correlation(rounds, 1000, KEY), 1.0);
+        }
+
+        // The full cipher is indistinguishable from random: with 20,000
+        // samples, the noise is about 0.007.
+        let full = round_correlation(32, 20_000, KEY);
+        assert!(full < 0.05, "{}", full);
+        assert!(round_correlation(2, 20_000, KEY) > full + 0.5);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_corpus_stats() {
+        // 100 distinct blocks, then 30 of them again.
+        let mut blocks: Vec<u64> = (0..100).collect();
+        blocks.extend(0..30);
+
+        let stats = corpus_stats(&blocks, KEY);
+        assert_eq!(stats.distinct_ciphertexts, 100);
+        assert_eq!(stats.collisions, 30);
+        assert_eq!(stats.byte_histogram.iter().sum::<u64>(), 130 * 8);
+
+        // The plaintext bytes are almost all zero, but the ciphertext's aren't.
+        assert!(stats.byte_histogram[0] < 20);
+
+        let empty = corpus_stats(&[], KEY);
+        assert_eq!(empty.distinct_ciphertexts, 0);
+        assert_eq!(empty.collisions, 0);
+        assert_eq!(empty.byte_histogram, [0; 256]);
+    }
+}
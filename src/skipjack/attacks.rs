@@ -0,0 +1,92 @@
+//! Attacks on Skipjack, for demonstrations.
+//!
+//! Nothing here threatens Skipjack in practice: the point is to show *why* it
+//! doesn't, by running an attack on a scale small enough to finish.
+
+use core::ops::Range;
+
+use rayon::prelude::*;
+
+use super::{encrypt_block, key_from_u128};
+
+// One more than the largest 80-bit key, as an integer.
+const KEY_SPACE: u128 = 1 << 80;
+
+/// Searches a range of keys, in parallel, for one that encrypts `plaintext` to
+/// `ciphertext`.
+///
+/// Keys are numbered as in `key_from_u128`, and any part of `key_range` past
+/// the largest 80-bit key is ignored. The candidates are spread across rayon's
+/// thread pool, and the lowest matching key in the range is returned, or
+/// `None` if there isn't one.
+///
+/// This is for a classroom: plant a key in a small range, and watch it be
+/// found. The whole key space is another matter. Even at tens of millions of
+/// keys per second per core, 2^80 keys take a billion core-years, which is
+/// why an 80-bit key is only "brute-forceable in theory". And over a range that
+/// large, a single (plaintext, ciphertext) pair isn't enough: about 2^16 keys
+/// map any given plaintext to any given ciphertext, so a match should be
+/// confirmed against a second pair.
+///
+/// # Arguments
+///
+/// * `plaintext` - A known plaintext block
+/// * `ciphertext` - The block that `plaintext` encrypts to under the unknown key
+/// * `key_range` - The candidate keys to try, as integers
+pub fn brute_force(plaintext: u64, ciphertext: u64, key_range: Range<u128>) -> Option<[u8; 10]> {
+    let end = key_range.end.min(KEY_SPACE);
+
+    (key_range.start..end)
+        .into_par_iter()
+        .map(|candidate| key_from_u128(candidate).expect("candidate is an 80-bit key"))
+        .find_first(|key| encrypt_block(plaintext, key) == ciphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::key_to_u128;
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_brute_force() {
+        let plaintext = 0x33221100ddccbbaa;
+        let ciphertext = encrypt_block(plaintext, KEY);
+
+        // Plant the key somewhere in the middle of a small range.
+        let planted = key_to_u128(&KEY);
+        let range = planted - 1000..planted + 3000;
+        assert_eq!(brute_force(plaintext, ciphertext, range), Some(KEY));
+
+        // At either end, too.
+        assert_eq!(
+            brute_force(plaintext, ciphertext, planted..planted + 1),
+            Some(KEY)
+        );
+        assert_eq!(
+            brute_force(plaintext, ciphertext, planted - 4095..planted + 1),
+            Some(KEY)
+        );
+
+        // A range without it finds nothing.
+        assert_eq!(
+            brute_force(plaintext, ciphertext, planted + 1..planted + 4096),
+            None
+        );
+        assert_eq!(brute_force(plaintext, ciphertext, planted..planted), None);
+    }
+
+    #[test]
+    fn test_brute_force_key_space_end() {
+        // The last key in the space is found, and nothing past it is tried.
+        let last = [0xff; 10];
+        let ciphertext = encrypt_block(0u64, last);
+
+        assert_eq!(
+            brute_force(0, ciphertext, KEY_SPACE - 16..u128::MAX),
+            Some(last)
+        );
+        assert_eq!(brute_force(0, ciphertext, KEY_SPACE..u128::MAX), None);
+    }
+}
@@ -0,0 +1,246 @@
+//! A bitsliced implementation of Skipjack, encrypting 64 blocks at once.
+//!
+//! In a bitsliced implementation, the state isn't stored block by block: instead,
+//! each `u64` "slice" holds one bit position of the state, with bit `i` of the
+//! slice belonging to block `i`. Every operation on the cipher's state becomes
+//! a handful of bitwise operations on slices, each of which operates on all 64
+//! blocks at once.
+//!
+//! The point of doing this is that there are no table lookups: the F table is
+//! replaced with a boolean circuit, so nothing the processor does depends on the
+//! data being encrypted. (The key still selects which slices get inverted, but
+//! it does so with masks, not branches.) That makes this implementation
+//! constant-time with respect to the data, at a considerable cost in speed.
+//!
+//! The circuit is F's algebraic normal form (ANF): each output bit of F is
+//! written as an XOR of ANDs ("monomials") of input bits. Rather than being
+//! transcribed by hand, the ANF is computed from the F table at compile time.
+
+use super::schedule::{KeySchedule, RoundKeys};
+use super::{uses_rule_a, F, ROUNDS};
+
+// One bit position of the state, across all 64 blocks.
+type Slice = u64;
+
+// A byte of the state: slice `i` holds bit `i` (with bit 0 least significant).
+type Byte = [Slice; 8];
+
+// A word of the state, in the same layout.
+type Word = [Slice; 16];
+
+// Computes the algebraic normal form of F.
+//
+// Bit `k` of `anf[u]` is set when output bit `k` of F includes the monomial
+// made up of the input bits set in `u`: for example, `anf[0b101]` says which
+// output bits include `x0 & x2`, and `anf[0]` which include the constant 1.
+// This is the Möbius transform of the table, which we can do for all eight
+// output bits at once by operating on whole bytes.
+const fn anf(table: &[u8; 256]) -> [u8; 256] {
+    let mut anf = *table;
+
+    let mut bit = 0;
+    while bit < 8 {
+        let mut u = 0;
+        while u < 256 {
+            if u & (1 << bit) != 0 {
+                anf[u] ^= anf[u ^ (1 << bit)];
+            }
+            u += 1;
+        }
+        bit += 1;
+    }
+
+    anf
+}
+
+static F_ANF: [u8; 256] = anf(&F);
+
+// Returns a slice of all ones if `bit` is 1, or all zeroes if it's 0.
+fn mask(bit: u8) -> Slice {
+    (bit as Slice & 1).wrapping_neg()
+}
+
+// The F table, as a circuit over sliced bytes.
+fn f(x: &Byte) -> Byte {
+    // First, every monomial: the AND of the input bits in each subset. Each
+    // one extends a smaller monomial by its highest bit.
+    let mut monomials = [0 as Slice; 256];
+    monomials[0] = !0;
+    for u in 1..256 {
+        let high = 7 - (u as u8).leading_zeros() as usize;
+        monomials[u] = monomials[u ^ (1 << high)] & x[high];
+    }
+
+    // Then each output bit is the XOR of its monomials.
+    let mut out = [0 as Slice; 8];
+    for (monomial, &coefficients) in monomials.iter().zip(F_ANF.iter()) {
+        for (k, bit) in out.iter_mut().enumerate() {
+            *bit ^= monomial & mask(coefficients >> k);
+        }
+    }
+
+    out
+}
+
+// XORs a byte that's the same in every block (like a key byte) into a sliced byte.
+fn xor_const_byte(x: &Byte, constant: u8) -> Byte {
+    let mut out = *x;
+    for (k, bit) in out.iter_mut().enumerate() {
+        *bit ^= mask(constant >> k);
+    }
+
+    out
+}
+
+fn xor_bytes(a: &Byte, b: &Byte) -> Byte {
+    let mut out = *a;
+    for (x, y) in out.iter_mut().zip(b.iter()) {
+        *x ^= y;
+    }
+
+    out
+}
+
+fn xor_words(a: &Word, b: &Word) -> Word {
+    let mut out = *a;
+    for (x, y) in out.iter_mut().zip(b.iter()) {
+        *x ^= y;
+    }
+
+    out
+}
+
+// XORs the (public) round counter into a sliced word.
+fn xor_counter(x: &Word, counter: u16) -> Word {
+    let mut out = *x;
+    for (k, bit) in out.iter_mut().enumerate() {
+        *bit ^= mask((counter >> k) as u8);
+    }
+
+    out
+}
+
+// Rule G, on sliced words. This has exactly the same structure as `rule_g`.
+fn rule_g(word: &Word, round_key: [u8; 4]) -> Word {
+    let mut g1 = [0; 8];
+    let mut g2 = [0; 8];
+    g1.copy_from_slice(&word[8..]);
+    g2.copy_from_slice(&word[..8]);
+
+    let g3 = xor_bytes(&f(&xor_const_byte(&g2, round_key[0])), &g1);
+    let g4 = xor_bytes(&f(&xor_const_byte(&g3, round_key[1])), &g2);
+    let g5 = xor_bytes(&f(&xor_const_byte(&g4, round_key[2])), &g3);
+    let g6 = xor_bytes(&f(&xor_const_byte(&g5, round_key[3])), &g4);
+
+    let mut result = [0; 16];
+    result[8..].copy_from_slice(&g5);
+    result[..8].copy_from_slice(&g6);
+
+    result
+}
+
+/// Encrypts 64 blocks at once with the given 80-bit secret key, in constant time.
+///
+/// The result is identical to calling `encrypt_block` on each block, but no
+/// memory access or branch depends on the blocks' contents.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt
+/// * `key` - The secret key to encrypt with
+pub fn encrypt_bitsliced(blocks: &[u64; 64], key: &[u8; 10]) -> [u64; 64] {
+    let schedule = KeySchedule::new(key);
+
+    // Transpose the blocks into slices. Word 1 (`words[0]`) is the most
+    // significant 16 bits of each block, as in `block_to_words`.
+    let mut words = [[0 as Slice; 16]; 4];
+    for (lane, &block) in blocks.iter().enumerate() {
+        for (i, word) in words.iter_mut().enumerate() {
+            let shift = 48 - 16 * i;
+            for (k, slice) in word.iter_mut().enumerate() {
+                *slice |= ((block >> (shift + k)) & 1) << lane;
+            }
+        }
+    }
+
+    // The same 32 rounds as `encrypt_block`.
+    for counter in 1..=ROUNDS as u16 {
+        let [w1, w2, w3, w4] = words;
+        let g = rule_g(&w1, schedule.round_key(counter - 1));
+
+        words = if uses_rule_a(counter) {
+            [xor_counter(&xor_words(&g, &w4), counter), g, w2, w3]
+        } else {
+            [w4, g, xor_counter(&xor_words(&w1, &w2), counter), w3]
+        };
+    }
+
+    // Transpose back into blocks.
+    let mut result = [0u64; 64];
+    for (lane, block) in result.iter_mut().enumerate() {
+        for (i, word) in words.iter().enumerate() {
+            let shift = 48 - 16 * i;
+            for (k, slice) in word.iter().enumerate() {
+                *block |= ((slice >> lane) & 1) << (shift + k);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encrypt_block;
+    use super::*;
+
+    #[test]
+    fn test_f_circuit() {
+        // Slice the 256 possible inputs into four batches of 64, and check every
+        // output of the circuit against the table.
+        for batch in 0..4 {
+            let mut x = [0 as Slice; 8];
+            for lane in 0..64 {
+                let input = batch * 64 + lane;
+                for (k, slice) in x.iter_mut().enumerate() {
+                    *slice |= (((input >> k) & 1) as Slice) << lane;
+                }
+            }
+
+            let out = f(&x);
+            for lane in 0..64 {
+                let mut output = 0u8;
+                for (k, slice) in out.iter().enumerate() {
+                    output |= (((slice >> lane) & 1) as u8) << k;
+                }
+                assert_eq!(output, F[batch * 64 + lane]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encrypt_bitsliced() {
+        let keys = [
+            [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+            [0; 10],
+            *b"0123456789",
+        ];
+
+        let mut blocks = [0u64; 64];
+        blocks[0] = 0x33221100ddccbbaa;
+        blocks[1] = 0xffffffffffffffff;
+        for (i, block) in blocks.iter_mut().enumerate().skip(2) {
+            *block = (i as u64).wrapping_mul(0x9e3779b97f4a7c15);
+        }
+
+        for key in keys.iter() {
+            let ciphertext = encrypt_bitsliced(&blocks, key);
+            for (c, &b) in ciphertext.iter().zip(blocks.iter()) {
+                assert_eq!(*c, encrypt_block(b, *key));
+            }
+        }
+
+        let ciphertext = encrypt_bitsliced(&blocks, &keys[0]);
+        assert_eq!(ciphertext[0], 0x2587cae27a12d300);
+    }
+}
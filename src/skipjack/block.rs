@@ -0,0 +1,183 @@
+use core::convert::TryFrom;
+use core::str::FromStr;
+
+use super::util::hex_decode_fixed;
+use super::Error;
+
+/// A single 64-bit Skipjack block.
+///
+/// Conversions to and from bytes are big-endian, matching the order in which
+/// the cipher splits a block into words (the high word first).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Block(pub u64);
+
+impl From<u64> for Block {
+    fn from(block: u64) -> Self {
+        Block(block)
+    }
+}
+
+impl From<Block> for u64 {
+    fn from(block: Block) -> Self {
+        block.0
+    }
+}
+
+impl From<[u8; 8]> for Block {
+    fn from(bytes: [u8; 8]) -> Self {
+        Block(u64::from_be_bytes(bytes))
+    }
+}
+
+impl From<Block> for [u8; 8] {
+    fn from(block: Block) -> Self {
+        block.0.to_be_bytes()
+    }
+}
+
+mod private {
+    // Prevents `IntoBlock` and `FromBlock` from being implemented outside this crate.
+    pub trait Sealed {}
+
+    impl Sealed for u64 {}
+    impl Sealed for [u8; 8] {}
+    impl Sealed for super::Block {}
+}
+
+/// Types that can be used as the input block to `encrypt_block` and `decrypt_block`.
+///
+/// This is implemented for `u64`, `[u8; 8]` (big-endian), and `Block`, and can't be
+/// implemented outside of this crate. Since `u64` is the only integer type that
+/// implements it, unsuffixed integer literals are inferred as `u64`.
+pub trait IntoBlock: private::Sealed {
+    /// Converts `self` into a 64-bit block.
+    fn into_block(self) -> u64;
+}
+
+/// Types that a 64-bit output block can be converted into, for
+/// `Cipher::encrypt_block_as` and `Cipher::decrypt_block_as`.
+///
+/// This is implemented for the same types as `IntoBlock`.
+pub trait FromBlock: private::Sealed {
+    /// Converts a 64-bit block into `Self`.
+    fn from_block(block: u64) -> Self;
+}
+
+impl IntoBlock for u64 {
+    fn into_block(self) -> u64 {
+        self
+    }
+}
+
+impl IntoBlock for [u8; 8] {
+    fn into_block(self) -> u64 {
+        u64::from_be_bytes(self)
+    }
+}
+
+impl IntoBlock for Block {
+    fn into_block(self) -> u64 {
+        self.0
+    }
+}
+
+impl FromBlock for u64 {
+    fn from_block(block: u64) -> Self {
+        block
+    }
+}
+
+impl FromBlock for [u8; 8] {
+    fn from_block(block: u64) -> Self {
+        block.to_be_bytes()
+    }
+}
+
+impl FromBlock for Block {
+    fn from_block(block: u64) -> Self {
+        Block(block)
+    }
+}
+
+impl TryFrom<&[u8]> for Block {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes = <[u8; 8]>::try_from(bytes).map_err(|_| Error::InvalidBlockLength)?;
+
+        Ok(Block::from(bytes))
+    }
+}
+
+impl FromStr for Block {
+    type Err = Error;
+
+    /// Parses a block written as exactly 16 hex digits (big-endian), in either case.
+    ///
+    /// Returns `Error::InvalidHex` if the string contains anything other than hex
+    /// digits, or `Error::InvalidBlockLength` if it isn't 8 bytes long.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match hex_decode_fixed::<8>(s) {
+            Ok(bytes) => Ok(Block::from(bytes)),
+            Err(Error::InvalidHexLength) => Err(Error::InvalidBlockLength),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decrypt_block, encrypt_block};
+    use super::*;
+
+    #[test]
+    fn test_try_from_slice() {
+        let bytes = [0x33, 0x22, 0x11, 0x00, 0xdd, 0xcc, 0xbb, 0xaa, 0x99];
+
+        // Exactly 8 bytes is a block, interpreted big-endian.
+        assert_eq!(Block::try_from(&bytes[..8]), Ok(Block(0x33221100ddccbbaa)));
+
+        // Anything shorter or longer is not.
+        assert_eq!(Block::try_from(&bytes[..7]), Err(Error::InvalidBlockLength));
+        assert_eq!(Block::try_from(&bytes[..]), Err(Error::InvalidBlockLength));
+    }
+
+    #[test]
+    fn test_into_block() {
+        let key: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+        let from_u64 = encrypt_block(0x33221100ddccbbaa, key);
+        let from_bytes = encrypt_block([0x33, 0x22, 0x11, 0x00, 0xdd, 0xcc, 0xbb, 0xaa], key);
+        let from_block = encrypt_block(Block(0x33221100ddccbbaa), key);
+
+        assert_eq!(from_u64, 0x2587cae27a12d300);
+        assert_eq!(from_bytes, from_u64);
+        assert_eq!(from_block, from_u64);
+
+        assert_eq!(
+            <[u8; 8]>::from_block(from_u64),
+            [0x25, 0x87, 0xca, 0xe2, 0x7a, 0x12, 0xd3, 0x00]
+        );
+        assert_eq!(
+            decrypt_block(<[u8; 8]>::from_block(from_u64), key),
+            0x33221100ddccbbaa
+        );
+    }
+
+    #[test]
+    fn test_block_from_str() {
+        assert_eq!("33221100ddccbbaa".parse(), Ok(Block(0x33221100ddccbbaa)));
+        assert_eq!("33221100DDCCBBAA".parse(), Ok(Block(0x33221100ddccbbaa)));
+
+        assert_eq!(
+            "33221100ddccbb".parse::<Block>(),
+            Err(Error::InvalidBlockLength)
+        );
+        assert_eq!(
+            "33221100ddccbbaa00".parse::<Block>(),
+            Err(Error::InvalidBlockLength)
+        );
+        assert_eq!("33221100ddccbbag".parse::<Block>(), Err(Error::InvalidHex));
+        assert_eq!("33221100ddccbba".parse::<Block>(), Err(Error::InvalidHex));
+    }
+}
@@ -0,0 +1,475 @@
+//! Cipher block chaining (CBC) mode.
+//!
+//! In CBC, each plaintext block is XOR'ed with the previous ciphertext block
+//! (or, for the first block, the initialization vector) before being encrypted.
+//! The IV should be unpredictable, and never reused with the same key.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+
+#[cfg(feature = "std")]
+use super::Error;
+use super::{decrypt_block, encrypt_block, Cipher, Direction};
+
+/// Encrypts the given blocks in CBC mode.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt with
+#[cfg(feature = "alloc")]
+pub fn encrypt_cbc(blocks: &[u64], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u64> {
+    transform(blocks, iv, key, Direction::Encrypt)
+}
+
+/// Decrypts the given blocks in CBC mode.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to decrypt with
+#[cfg(feature = "alloc")]
+pub fn decrypt_cbc(blocks: &[u64], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u64> {
+    transform(blocks, iv, key, Direction::Decrypt)
+}
+
+/// Encrypts the given blocks in CBC mode, in place.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt with
+pub fn encrypt_cbc_inplace(blocks: &mut [u64], iv: u64, key: impl Borrow<[u8; 10]>) {
+    transform_inplace(blocks, iv, key, Direction::Encrypt);
+}
+
+/// Decrypts the given blocks in CBC mode, in place.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to decrypt with
+pub fn decrypt_cbc_inplace(blocks: &mut [u64], iv: u64, key: impl Borrow<[u8; 10]>) {
+    transform_inplace(blocks, iv, key, Direction::Decrypt);
+}
+
+/// Encrypts or decrypts the given blocks in CBC mode, depending on `direction`.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt or decrypt with
+/// * `direction` - Whether to encrypt or decrypt
+#[cfg(feature = "alloc")]
+pub fn transform(
+    blocks: &[u64],
+    iv: u64,
+    key: impl Borrow<[u8; 10]>,
+    direction: Direction,
+) -> Vec<u64> {
+    let mut output = blocks.to_vec();
+    transform_inplace(&mut output, iv, key, direction);
+
+    output
+}
+
+/// Encrypts or decrypts the given blocks in CBC mode, in place.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt or decrypt with
+/// * `direction` - Whether to encrypt or decrypt
+pub fn transform_inplace(
+    blocks: &mut [u64],
+    iv: u64,
+    key: impl Borrow<[u8; 10]>,
+    direction: Direction,
+) {
+    transform_with(&Cipher::new(key), blocks, iv, direction);
+}
+
+// CBC itself, shared by the free functions and the `Cipher` methods.
+fn transform_with<M>(cipher: &Cipher<M>, blocks: &mut [u64], iv: u64, direction: Direction) {
+    let mut chain = iv;
+
+    match direction {
+        Direction::Encrypt => {
+            // When encrypting, the chaining value is the ciphertext we just wrote,
+            // so overwriting each block as we go is harmless.
+            for block in blocks.iter_mut() {
+                *block = cipher.encrypt_block(*block ^ chain);
+                chain = *block;
+            }
+        }
+        Direction::Decrypt => {
+            // When decrypting, the chaining value for the next block is the
+            // ciphertext of this one. That's exactly what we're about to
+            // overwrite, so we have to save it first.
+            for block in blocks.iter_mut() {
+                let ciphertext = *block;
+                *block = cipher.decrypt_block(ciphertext) ^ chain;
+                chain = ciphertext;
+            }
+        }
+    }
+}
+
+impl<M> Cipher<M> {
+    /// Encrypts the given blocks in CBC mode, like `cbc::encrypt_cbc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    /// * `iv` - The initialization vector
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_cbc(&self, blocks: &[u64], iv: u64) -> Vec<u64> {
+        let mut output = blocks.to_vec();
+        self.encrypt_cbc_inplace(&mut output, iv);
+
+        output
+    }
+
+    /// Decrypts the given blocks in CBC mode, like `cbc::decrypt_cbc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to decrypt
+    /// * `iv` - The initialization vector
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_cbc(&self, blocks: &[u64], iv: u64) -> Vec<u64> {
+        let mut output = blocks.to_vec();
+        self.decrypt_cbc_inplace(&mut output, iv);
+
+        output
+    }
+
+    /// Encrypts the given blocks in CBC mode, in place, like `cbc::encrypt_cbc_inplace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    /// * `iv` - The initialization vector
+    pub fn encrypt_cbc_inplace(&self, blocks: &mut [u64], iv: u64) {
+        transform_with(self, blocks, iv, Direction::Encrypt);
+    }
+
+    /// Decrypts the given blocks in CBC mode, in place, like `cbc::decrypt_cbc_inplace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to decrypt
+    /// * `iv` - The initialization vector
+    pub fn decrypt_cbc_inplace(&self, blocks: &mut [u64], iv: u64) {
+        transform_with(self, blocks, iv, Direction::Decrypt);
+    }
+}
+
+/// A CBC session that refuses to encrypt twice under the same IV.
+///
+/// Reusing an IV with the same key is a classic CBC mistake: it reveals which
+/// messages start with the same blocks. A session remembers every IV it has
+/// encrypted with, and `encrypt` returns `Error::IvReused` rather than use one
+/// again.
+///
+/// This only catches reuse *within one session*: two sessions with the same
+/// key know nothing of each other's IVs, and neither knows about IVs used
+/// before it was created. The IVs are kept for the life of the session, at a
+/// cost of roughly 16 bytes each, so a long-lived session should be replaced
+/// (along with its key) from time to time. And it can't tell an IV that's
+/// unique from one that's unpredictable, which CBC also needs.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct CbcSession {
+    cipher: Cipher,
+    seen_ivs: HashSet<u64>,
+}
+
+#[cfg(feature = "std")]
+impl CbcSession {
+    /// Creates a new session with the given key, and no IVs used yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to encrypt and decrypt with
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        CbcSession {
+            cipher: Cipher::new(key),
+            seen_ivs: HashSet::new(),
+        }
+    }
+
+    /// Encrypts the given blocks in CBC mode, unless this session has already
+    /// used `iv`, in which case `Error::IvReused` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    /// * `iv` - The initialization vector, which must be new to this session
+    pub fn encrypt(&mut self, blocks: &[u64], iv: u64) -> Result<Vec<u64>, Error> {
+        if !self.seen_ivs.insert(iv) {
+            return Err(Error::IvReused);
+        }
+
+        Ok(self.cipher.encrypt_cbc(blocks, iv))
+    }
+
+    /// Decrypts the given blocks in CBC mode.
+    ///
+    /// Decrypting under an IV doesn't count as using it: the same IV is needed
+    /// to decrypt as to encrypt, so this never fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to decrypt
+    /// * `iv` - The initialization vector
+    pub fn decrypt(&self, blocks: &[u64], iv: u64) -> Vec<u64> {
+        self.cipher.decrypt_cbc(blocks, iv)
+    }
+}
+
+/// An iterator that lazily decrypts CBC ciphertext blocks from another iterator.
+///
+/// Each block is decrypted as it's pulled from the underlying iterator, so the
+/// ciphertext never has to be held in memory all at once (e.g. when it comes
+/// from an `io::BlockReader`). Between blocks, this holds on to the previous
+/// ciphertext block, which is what the next one is chained with.
+///
+/// Created by `CbcDecryptExt::decrypt_cbc`.
+#[derive(Clone, Debug)]
+pub struct DecryptBlocks<I> {
+    blocks: I,
+    cipher: Cipher,
+    chain: u64,
+}
+
+impl<I: Iterator<Item = u64>> Iterator for DecryptBlocks<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let ciphertext = self.blocks.next()?;
+        let plaintext = self.cipher.decrypt_block(ciphertext) ^ self.chain;
+        self.chain = ciphertext;
+
+        Some(plaintext)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.blocks.size_hint()
+    }
+}
+
+/// An extension trait for decrypting iterators of ciphertext blocks in CBC mode.
+pub trait CbcDecryptExt: Iterator<Item = u64> + Sized {
+    /// Returns an iterator that decrypts these blocks in CBC mode as they're
+    /// pulled from it. See `DecryptBlocks`.
+    ///
+    /// # Arguments
+    ///
+    /// * `iv` - The initialization vector
+    /// * `key` - The secret key to decrypt with
+    fn decrypt_cbc(self, iv: u64, key: impl Borrow<[u8; 10]>) -> DecryptBlocks<Self> {
+        DecryptBlocks {
+            blocks: self,
+            cipher: Cipher::new(key),
+            chain: iv,
+        }
+    }
+}
+
+impl<I: Iterator<Item = u64>> CbcDecryptExt for I {}
+
+/// Encrypts a single block in CBC mode, in place, and advances the chain.
+///
+/// `block` is XOR'ed with `chain` and then encrypted, and `chain` becomes the
+/// resulting ciphertext, ready for the next block. Start with `chain` set to the
+/// IV. This doesn't allocate, so it can be used to build CBC over any source of
+/// blocks. Blocks are read big-endian, as everywhere else in this crate.
+///
+/// # Arguments
+///
+/// * `block` - The block to encrypt
+/// * `chain` - The previous ciphertext block (or the IV)
+/// * `key` - The secret key to encrypt with
+pub fn cbc_step_encrypt(block: &mut [u8; 8], chain: &mut [u8; 8], key: &[u8; 10]) {
+    cbc_step_encrypt_with(block, chain, |chained| encrypt_block(chained, key));
+}
+
+// `cbc_step_encrypt`, with the block cipher supplied by the caller: either the
+// key (as above), or a `Cipher` with its precomputed schedule.
+pub(crate) fn cbc_step_encrypt_with(
+    block: &mut [u8; 8],
+    chain: &mut [u8; 8],
+    encrypt: impl FnOnce(u64) -> u64,
+) {
+    let chained = u64::from_be_bytes(*block) ^ u64::from_be_bytes(*chain);
+
+    *block = encrypt(chained).to_be_bytes();
+    *chain = *block;
+}
+
+/// Decrypts a single block in CBC mode, in place, and advances the chain.
+///
+/// `block` is decrypted and then XOR'ed with `chain`, and `chain` becomes the
+/// incoming ciphertext, ready for the next block. Start with `chain` set to the IV.
+///
+/// # Arguments
+///
+/// * `block` - The block to decrypt
+/// * `chain` - The previous ciphertext block (or the IV)
+/// * `key` - The secret key to decrypt with
+pub fn cbc_step_decrypt(block: &mut [u8; 8], chain: &mut [u8; 8], key: &[u8; 10]) {
+    cbc_step_decrypt_with(block, chain, |ciphertext| decrypt_block(ciphertext, key));
+}
+
+// `cbc_step_decrypt`, with the block cipher supplied by the caller.
+pub(crate) fn cbc_step_decrypt_with(
+    block: &mut [u8; 8],
+    chain: &mut [u8; 8],
+    decrypt: impl FnOnce(u64) -> u64,
+) {
+    // Save the ciphertext before we overwrite it: it's the next chaining value.
+    let ciphertext = *block;
+
+    *block = (decrypt(u64::from_be_bytes(ciphertext)) ^ u64::from_be_bytes(*chain)).to_be_bytes();
+    *chain = ciphertext;
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_first_block() {
+        // With a zero IV, the first block is just ECB.
+        let ciphertext = encrypt_cbc(&[0x33221100ddccbbaa, 0], 0, KEY);
+
+        assert_eq!(ciphertext[0], 0x2587cae27a12d300);
+        assert_eq!(ciphertext[1], encrypt_block(0x2587cae27a12d300u64, KEY));
+    }
+
+    #[test]
+    fn test_inplace_matches_allocating() {
+        let plaintext = [0x33221100ddccbbaa, 0x33221100ddccbbaa, 0, 1, 2];
+
+        let ciphertext = encrypt_cbc(&plaintext, IV, KEY);
+        assert_ne!(ciphertext[0], ciphertext[1]);
+
+        let mut blocks = plaintext;
+        encrypt_cbc_inplace(&mut blocks, IV, KEY);
+        assert_eq!(blocks[..], ciphertext[..]);
+
+        decrypt_cbc_inplace(&mut blocks, IV, KEY);
+        assert_eq!(blocks, plaintext);
+        assert_eq!(decrypt_cbc(&ciphertext, IV, KEY), plaintext);
+    }
+
+    #[test]
+    fn test_transform() {
+        let plaintext = [0x33221100ddccbbaa, 0, 1, 0xffffffffffffffff];
+        let ciphertext = encrypt_cbc(&plaintext, IV, KEY);
+
+        assert_eq!(
+            transform(&plaintext, IV, KEY, Direction::Encrypt),
+            ciphertext
+        );
+        assert_eq!(
+            transform(&ciphertext, IV, KEY, Direction::Decrypt),
+            plaintext
+        );
+
+        let mut blocks = plaintext;
+        transform_inplace(&mut blocks, IV, KEY, Direction::Encrypt);
+        assert_eq!(blocks[..], ciphertext[..]);
+        transform_inplace(&mut blocks, IV, KEY, Direction::Decrypt);
+        assert_eq!(blocks, plaintext);
+    }
+
+    #[test]
+    fn test_cipher_methods() {
+        let plaintext = [0x33221100ddccbbaa, 0, 1, 0xffffffffffffffff];
+        let cipher = Cipher::new(KEY);
+
+        let ciphertext = cipher.encrypt_cbc(&plaintext, IV);
+        assert_eq!(ciphertext, encrypt_cbc(&plaintext, IV, KEY));
+        assert_eq!(cipher.decrypt_cbc(&ciphertext, IV), plaintext);
+
+        let mut blocks = plaintext;
+        cipher.encrypt_cbc_inplace(&mut blocks, IV);
+        assert_eq!(blocks[..], ciphertext[..]);
+        cipher.decrypt_cbc_inplace(&mut blocks, IV);
+        assert_eq!(blocks, plaintext);
+    }
+
+    #[test]
+    fn test_cbc_step() {
+        let plaintext = [0x33221100ddccbbaa, 0, 0x33221100ddccbbaa];
+        let ciphertext = encrypt_cbc(&plaintext, IV, KEY);
+
+        let mut chain = IV.to_be_bytes();
+        for (&p, &c) in plaintext.iter().zip(ciphertext.iter()) {
+            let mut block = p.to_be_bytes();
+            cbc_step_encrypt(&mut block, &mut chain, &KEY);
+
+            assert_eq!(block, c.to_be_bytes());
+            assert_eq!(chain, c.to_be_bytes());
+        }
+
+        let mut chain = IV.to_be_bytes();
+        for (&p, &c) in plaintext.iter().zip(ciphertext.iter()) {
+            let mut block = c.to_be_bytes();
+            cbc_step_decrypt(&mut block, &mut chain, &KEY);
+
+            assert_eq!(block, p.to_be_bytes());
+            assert_eq!(chain, c.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decrypt_blocks() {
+        let plaintext: Vec<u64> = (0..20).map(|i| i * 0x0101010101010101).collect();
+        let ciphertext = encrypt_cbc(&plaintext, IV, KEY);
+
+        // Nothing is decrypted until it's asked for.
+        let mut decrypted = ciphertext.iter().copied().decrypt_cbc(IV, KEY);
+        assert_eq!(decrypted.size_hint(), (20, Some(20)));
+        assert_eq!(decrypted.next(), Some(plaintext[0]));
+
+        let rest: Vec<u64> = decrypted.collect();
+        assert_eq!(rest[..], plaintext[1..]);
+        assert_eq!(decrypt_cbc(&ciphertext, IV, KEY), plaintext);
+
+        assert_eq!(core::iter::empty().decrypt_cbc(IV, KEY).next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_session_rejects_reused_iv() {
+        let plaintext = [0x33221100ddccbbaa, 0, 1];
+        let mut session = CbcSession::new(KEY);
+
+        let ciphertext = session.encrypt(&plaintext, IV).unwrap();
+        assert_eq!(ciphertext, encrypt_cbc(&plaintext, IV, KEY));
+        assert_eq!(session.decrypt(&ciphertext, IV), plaintext);
+
+        // Distinct IVs are fine; the same IV again isn't, even for other data.
+        assert!(session.encrypt(&plaintext, IV + 1).is_ok());
+        assert_eq!(session.encrypt(&plaintext, IV), Err(Error::IvReused));
+        assert_eq!(session.encrypt(&[1, 2, 3], IV + 1), Err(Error::IvReused));
+
+        // A new session knows nothing of the old one's IVs.
+        assert!(CbcSession::new(KEY).encrypt(&plaintext, IV).is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+//! 8-bit cipher feedback (CFB-8) mode.
+//!
+//! CFB-8 encrypts one byte at a time. A 64-bit shift register starts out as the
+//! initialization vector; for each byte, the register is encrypted, and the most
+//! significant byte of the result is XOR'ed with the data. The resulting
+//! ciphertext byte is then shifted into the register.
+//!
+//! This costs a full block encryption per byte, but it needs no padding and
+//! resynchronizes by itself after a dropped or corrupted byte, which is why
+//! some old serial-line protocols used it. The IV should be unpredictable, and
+//! never reused with the same key.
+
+use core::borrow::Borrow;
+
+use super::{Cipher, Direction};
+
+/// Encrypts the given data in CFB-8 mode, in place.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt with
+pub fn encrypt_inplace(data: &mut [u8], iv: u64, key: impl Borrow<[u8; 10]>) {
+    transform_inplace(data, iv, key, Direction::Encrypt);
+}
+
+/// Decrypts the given data in CFB-8 mode, in place.
+///
+/// # Arguments
+///
+/// * `data` - The data to decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to decrypt with
+pub fn decrypt_inplace(data: &mut [u8], iv: u64, key: impl Borrow<[u8; 10]>) {
+    transform_inplace(data, iv, key, Direction::Decrypt);
+}
+
+/// Encrypts or decrypts the given data in CFB-8 mode, in place.
+///
+/// Note that both directions use Skipjack's encryption function: only the
+/// choice of which byte is fed back into the register differs.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt or decrypt with
+/// * `direction` - Whether to encrypt or decrypt
+pub fn transform_inplace(
+    data: &mut [u8],
+    iv: u64,
+    key: impl Borrow<[u8; 10]>,
+    direction: Direction,
+) {
+    transform_with(&Cipher::new(key), data, iv, direction);
+}
+
+// CFB-8 itself, shared by the free functions and the `Cipher` methods.
+fn transform_with<M>(cipher: &Cipher<M>, data: &mut [u8], iv: u64, direction: Direction) {
+    let mut register = iv;
+
+    for byte in data.iter_mut() {
+        let keystream = (cipher.encrypt_block(register) >> 56) as u8;
+
+        // The register is always fed the ciphertext byte: the one we're about
+        // to write when encrypting, or the one we're about to overwrite when
+        // decrypting.
+        let ciphertext = match direction {
+            Direction::Encrypt => *byte ^ keystream,
+            Direction::Decrypt => *byte,
+        };
+
+        *byte ^= keystream;
+        register = (register << 8) | ciphertext as u64;
+    }
+}
+
+impl<M> Cipher<M> {
+    /// Encrypts the given data in CFB-8 mode, in place, like `cfb8::encrypt_inplace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to encrypt
+    /// * `iv` - The initialization vector
+    pub fn encrypt_cfb8_inplace(&self, data: &mut [u8], iv: u64) {
+        transform_with(self, data, iv, Direction::Encrypt);
+    }
+
+    /// Decrypts the given data in CFB-8 mode, in place, like `cfb8::decrypt_inplace`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to decrypt
+    /// * `iv` - The initialization vector
+    pub fn decrypt_cfb8_inplace(&self, data: &mut [u8], iv: u64) {
+        transform_with(self, data, iv, Direction::Decrypt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_roundtrip() {
+        let plaintext = b"sixteen byte msg and more";
+
+        let mut data = *plaintext;
+        encrypt_inplace(&mut data, IV, KEY);
+        assert_eq!(
+            data[..],
+            [
+                0xf0, 0x94, 0x80, 0xcc, 0x09, 0x76, 0x08, 0x34, 0x0d, 0xf8, 0x99, 0x74, 0x55, 0x25,
+                0x37, 0x66, 0x50, 0xcc, 0x97, 0x67, 0x03, 0x27, 0x3a, 0x9d, 0x43,
+            ][..]
+        );
+
+        decrypt_inplace(&mut data, IV, KEY);
+        assert_eq!(&data, plaintext);
+    }
+
+    #[test]
+    fn test_cipher_methods() {
+        let plaintext = *b"sixteen byte msg and more";
+        let cipher = Cipher::new(KEY);
+
+        let mut expected = plaintext;
+        encrypt_inplace(&mut expected, IV, KEY);
+
+        let mut data = plaintext;
+        cipher.encrypt_cfb8_inplace(&mut data, IV);
+        assert_eq!(data, expected);
+
+        cipher.decrypt_cfb8_inplace(&mut data, IV);
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn test_resynchronizes() {
+        let plaintext = [0x5a; 32];
+
+        let mut data = plaintext;
+        encrypt_inplace(&mut data, IV, KEY);
+        data[4] ^= 0xff;
+
+        // A corrupted byte garbles itself and the 8 bytes after it (while it's
+        // in the shift register), but nothing beyond that.
+        decrypt_inplace(&mut data, IV, KEY);
+        assert_eq!(data[..4], plaintext[..4]);
+        assert_ne!(data[4], plaintext[4]);
+        assert_eq!(data[13..], plaintext[13..]);
+    }
+}
@@ -0,0 +1,609 @@
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::fmt;
+use core::marker::PhantomData;
+
+use super::schedule::KeySchedule;
+#[cfg(any(test, feature = "test-util"))]
+use super::schedule::RoundKeys;
+use super::typed::{Ecb, TypedMode};
+use super::{
+    block_to_words, decrypt_rounds, encrypt_rounds, encrypt_schedule, is_weak_key, rule_a, rule_b,
+    uses_rule_a, words_to_block, Error, FromBlock, IntoBlock, ROUNDS, ROUND_SCHEDULE,
+};
+
+// The number of blocks that `encrypt_blocks_interleaved` encrypts side by side.
+const INTERLEAVE: usize = 8;
+
+// The number of blocks in each unit of work handed to rayon by `encrypt_par`.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK: usize = 4096;
+
+/// A Skipjack cipher, configured with a secret key.
+///
+/// This is a convenience for callers that encrypt or decrypt many blocks with
+/// the same key: each method is equivalent to the free function of the same
+/// name, without passing the key every time.
+///
+/// The four key bytes used in each of the 32 rounds are computed once, when the
+/// cipher is created, so that each round only loads them from an array rather
+/// than indexing into the secret key. The key itself isn't kept, and
+/// `Cipher::new(&key)` doesn't copy it, but the schedule is no less sensitive:
+/// it holds each key byte about 13 times over, and the key can be read straight
+/// back out of it.
+///
+/// The type parameter is a mode of operation (see `typed`), used by `process`.
+/// It defaults to `Ecb`, so a plain `Cipher` is an ECB cipher; `with_mode`
+/// changes it. Every other method works the same whatever the mode.
+#[derive(Clone)]
+pub struct Cipher<M = Ecb> {
+    schedule: KeySchedule,
+    #[cfg(feature = "alloc")]
+    on_block: Option<Arc<dyn Fn() + Send + Sync>>,
+    // A function pointer, so that the mode doesn't affect `Send` or `Sync`.
+    mode: PhantomData<fn() -> M>,
+}
+
+impl Cipher {
+    /// Creates a new cipher with the given 80-bit secret key.
+    ///
+    /// Any key is accepted, including ones that `is_weak_key` flags; see
+    /// `new_checked` for a constructor that rejects them.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to encrypt and decrypt with, as a `[u8; 10]` or a reference to one
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        Cipher {
+            schedule: KeySchedule::new(key.borrow()),
+            #[cfg(feature = "alloc")]
+            on_block: None,
+            mode: PhantomData,
+        }
+    }
+
+    /// Creates a new cipher with the given 80-bit secret key, rejecting weak keys.
+    ///
+    /// Returns `Error::WeakKey` if `is_weak_key` flags the key, which almost
+    /// always means it came from a bug or a placeholder rather than a random
+    /// source. Otherwise, this is the same as `new`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to encrypt and decrypt with, as a `[u8; 10]` or a reference to one
+    pub fn new_checked(key: impl Borrow<[u8; 10]>) -> Result<Self, Error> {
+        let key = key.borrow();
+        if is_weak_key(key) {
+            return Err(Error::WeakKey);
+        }
+
+        Ok(Cipher::new(key))
+    }
+}
+
+impl<M> Cipher<M> {
+    /// Returns a new cipher with a different key.
+    ///
+    /// This is the same as `Cipher::new(new_key).with_mode::<M>()`: nothing but
+    /// the mode carries over from this cipher. It exists so that key rotation
+    /// reads naturally in code that holds a `Cipher` rather than a key, since
+    /// `Cipher` never gives its key back.
+    ///
+    /// # Arguments
+    ///
+    /// * `new_key` - The secret key for the new cipher, as a `[u8; 10]` or a reference to one
+    pub fn with_key(&self, new_key: impl Borrow<[u8; 10]>) -> Cipher<M> {
+        Cipher {
+            schedule: KeySchedule::new(new_key.borrow()),
+            #[cfg(feature = "alloc")]
+            on_block: None,
+            mode: PhantomData,
+        }
+    }
+
+    /// Returns this cipher, with its mode of operation changed to `N`.
+    ///
+    /// The key schedule (and `on_block` callback, if any) carries over. Only
+    /// `process` depends on the mode.
+    pub fn with_mode<N: TypedMode>(self) -> Cipher<N> {
+        Cipher {
+            schedule: self.schedule,
+            #[cfg(feature = "alloc")]
+            on_block: self.on_block,
+            mode: PhantomData,
+        }
+    }
+
+    /// Returns this cipher, with a callback to run once for every block that
+    /// the bulk methods (`encrypt_blocks_inplace`, `encrypt_blocks_interleaved`,
+    /// `encrypt_block_batch`, and `encrypt_par`) encrypt.
+    ///
+    /// This is for auditing and metrics, e.g. counting blocks processed, without
+    /// tying this crate to a logging framework. The callback is told only that
+    /// a block was processed: never which one, what it encrypted to, or the key.
+    /// It may be called from several threads at once (by `encrypt_par`), and
+    /// it's shared between clones of this cipher and kept by `with_mode`, but
+    /// not kept by `with_key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - The function to call for each block
+    #[cfg(feature = "alloc")]
+    pub fn with_on_block(mut self, callback: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_block = Some(Arc::new(callback));
+        self
+    }
+
+    // Runs the `on_block` callback, if there is one, for `n` processed blocks.
+    #[inline]
+    fn blocks_processed(&self, n: usize) {
+        #[cfg(feature = "alloc")]
+        if let Some(on_block) = &self.on_block {
+            for _ in 0..n {
+                on_block();
+            }
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        let _ = n;
+    }
+
+    /// Returns the secret key that this cipher was created with.
+    ///
+    /// **For tests only**, like `Default`: this is only available in this
+    /// crate's own tests, or with the `test-util` feature.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn key_bytes(&self) -> [u8; 10] {
+        // The key is consumed four bytes per step, so the first three steps'
+        // round keys cover all ten bytes (and then some).
+        let mut key = [0u8; 10];
+        key[..4].copy_from_slice(&self.schedule.round_key(0));
+        key[4..8].copy_from_slice(&self.schedule.round_key(1));
+        key[8..].copy_from_slice(&self.schedule.round_key(2)[..2]);
+
+        key
+    }
+
+    /// Encrypts the given 64-bit block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to encrypt, as a `u64`, `[u8; 8]`, or `Block`
+    pub fn encrypt_block(&self, block: impl IntoBlock) -> u64 {
+        let mut words = block_to_words(block.into_block());
+        encrypt_rounds(&mut words, &self.schedule);
+        words_to_block(words)
+    }
+
+    /// Encrypts the given 64-bit block, checking the cipher's internal invariants.
+    ///
+    /// In debug builds, this checks that the round counter finished where it
+    /// should, one past round 32, and returns `Error::InternalInvariant` if it
+    /// didn't. That can't happen unless the implementation itself is broken
+    /// (say, by a bad refactoring), but it's cheap to rule out. In release
+    /// builds, the check is compiled out, and this is `encrypt_block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to encrypt, as a `u64`, `[u8; 8]`, or `Block`
+    pub fn encrypt_block_checked(&self, block: impl IntoBlock) -> Result<u64, Error> {
+        let mut words = block_to_words(block.into_block());
+        let counter = encrypt_schedule(&mut words, &self.schedule, &ROUND_SCHEDULE);
+
+        if cfg!(debug_assertions) && counter as usize != ROUNDS + 1 {
+            return Err(Error::InternalInvariant);
+        }
+
+        Ok(words_to_block(words))
+    }
+
+    /// Decrypts the given 64-bit block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to decrypt, as a `u64`, `[u8; 8]`, or `Block`
+    pub fn decrypt_block(&self, block: impl IntoBlock) -> u64 {
+        let mut words = block_to_words(block.into_block());
+        decrypt_rounds(&mut words, &self.schedule);
+        words_to_block(words)
+    }
+
+    /// Encrypts the given 64-bit block, returning it as a `u64`, `[u8; 8]`,
+    /// or `Block`.
+    ///
+    /// This is `encrypt_block`, with the result converted to whichever of
+    /// those the caller asks for: `cipher.encrypt_block_as::<[u8; 8]>(block)`
+    /// gives the ciphertext as big-endian bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to encrypt, as a `u64`, `[u8; 8]`, or `Block`
+    pub fn encrypt_block_as<O: FromBlock>(&self, block: impl IntoBlock) -> O {
+        O::from_block(self.encrypt_block(block))
+    }
+
+    /// Decrypts the given 64-bit block, returning it as a `u64`, `[u8; 8]`,
+    /// or `Block`.
+    ///
+    /// This is `decrypt_block`, with the result converted as in
+    /// `encrypt_block_as`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to decrypt, as a `u64`, `[u8; 8]`, or `Block`
+    pub fn decrypt_block_as<O: FromBlock>(&self, block: impl IntoBlock) -> O {
+        O::from_block(self.decrypt_block(block))
+    }
+
+    /// Encrypts each of the given blocks in place (ECB).
+    ///
+    /// With the `simd` feature, blocks are encrypted four at a time with
+    /// `simd::encrypt_blocks4`, and any remaining blocks one at a time.
+    /// Without it, every block is encrypted one at a time. The results are
+    /// identical either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    pub fn encrypt_blocks_inplace(&self, blocks: &mut [u64]) {
+        #[cfg(feature = "simd")]
+        let blocks = {
+            let mut chunks = blocks.chunks_exact_mut(4);
+            for chunk in &mut chunks {
+                let lanes = [chunk[0], chunk[1], chunk[2], chunk[3]];
+                chunk.copy_from_slice(&super::simd::encrypt_blocks4_with(lanes, &self.schedule));
+                self.blocks_processed(4);
+            }
+            chunks.into_remainder()
+        };
+
+        for block in blocks.iter_mut() {
+            *block = self.encrypt_block(*block);
+            self.blocks_processed(1);
+        }
+    }
+
+    /// Encrypts each of the given blocks in place (ECB), several at a time.
+    ///
+    /// Each round of Skipjack depends on the one before it, so encrypting a
+    /// single block leaves the processor waiting on one long chain of table
+    /// lookups. Here, blocks are taken in groups of eight, and each round is
+    /// applied to all eight before moving on to the next: the eight chains are
+    /// independent, so their latencies overlap. Any remaining blocks are
+    /// encrypted one at a time. Unlike the `simd` feature, this is plain scalar
+    /// code, and the results are identical to `encrypt_block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    pub fn encrypt_blocks_interleaved(&self, blocks: &mut [u64]) {
+        let mut chunks = blocks.chunks_exact_mut(INTERLEAVE);
+        for chunk in &mut chunks {
+            let mut states = [[0u16; 4]; INTERLEAVE];
+            for (state, &block) in states.iter_mut().zip(chunk.iter()) {
+                *state = block_to_words(block);
+            }
+
+            // The same 32 rounds as `encrypt_rounds`, with each round applied to
+            // every block in the group. Each block gets its own copy of the
+            // counter, since the rules advance it.
+            for counter in 1..=ROUNDS as u16 {
+                for state in states.iter_mut() {
+                    let mut step = counter;
+                    if uses_rule_a(counter) {
+                        rule_a(state, &mut step, &self.schedule);
+                    } else {
+                        rule_b(state, &mut step, &self.schedule);
+                    }
+                }
+            }
+
+            for (block, &state) in chunk.iter_mut().zip(states.iter()) {
+                *block = words_to_block(state);
+            }
+            self.blocks_processed(INTERLEAVE);
+        }
+
+        for block in chunks.into_remainder() {
+            *block = self.encrypt_block(*block);
+            self.blocks_processed(1);
+        }
+    }
+
+    /// Encrypts a fixed number of blocks (ECB), returning them as an array.
+    ///
+    /// This is `encrypt_blocks_interleaved` for callers who know the number of
+    /// blocks at compile time: each round is applied to all `N` blocks before
+    /// moving on to the next, and since `N` is a constant, the compiler is free
+    /// to keep everything on the stack and unroll the loops over the blocks.
+    /// It's plain scalar code for any `N` (unlike `simd::encrypt_blocks4`), and
+    /// the results are identical to `encrypt_block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    pub fn encrypt_block_batch<const N: usize>(&self, blocks: [u64; N]) -> [u64; N] {
+        let mut states = blocks.map(block_to_words);
+
+        // The same rounds as `encrypt_blocks_interleaved`, with each block
+        // getting its own copy of the counter.
+        for counter in 1..=ROUNDS as u16 {
+            for state in states.iter_mut() {
+                let mut step = counter;
+                if uses_rule_a(counter) {
+                    rule_a(state, &mut step, &self.schedule);
+                } else {
+                    rule_b(state, &mut step, &self.schedule);
+                }
+            }
+        }
+        self.blocks_processed(N);
+
+        states.map(words_to_block)
+    }
+
+    /// Encrypts each of the given blocks in place (ECB), in parallel.
+    ///
+    /// The slice is split into chunks of a few thousand blocks, which rayon
+    /// spreads across its thread pool; each chunk is encrypted with
+    /// `encrypt_blocks_interleaved`, using this cipher's precomputed key
+    /// schedule. The results are identical to `encrypt_block`.
+    ///
+    /// Every block is independent, so throughput should scale close to linearly
+    /// with the number of cores, once there are enough blocks to go around.
+    /// Below a chunk or two, this is no faster than `encrypt_blocks_interleaved`
+    /// (and pays for the trip to the thread pool).
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    #[cfg(feature = "rayon")]
+    pub fn encrypt_par(&self, blocks: &mut [u64]) {
+        use rayon::prelude::*;
+
+        blocks
+            .par_chunks_mut(PAR_CHUNK)
+            .for_each(|chunk| self.encrypt_blocks_interleaved(chunk));
+    }
+}
+
+/// A cipher with the NIST test key, `00 99 88 77 66 55 44 33 22 11`.
+///
+/// **For tests only.** The key is public, so anything encrypted with it is
+/// readable by anyone. This is only available in this crate's own tests, or with
+/// the `test-util` feature, which should never be enabled outside of
+/// `[dev-dependencies]`.
+#[cfg(any(test, feature = "test-util"))]
+impl Default for Cipher {
+    fn default() -> Self {
+        Cipher::new([0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11])
+    }
+}
+
+impl<M> fmt::Debug for Cipher<M> {
+    // Never include the key in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cipher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decrypt_block, encrypt_block, Block};
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_cipher() {
+        let cipher = Cipher::new(KEY);
+
+        assert_eq!(cipher.encrypt_block(0x33221100ddccbbaa), 0x2587cae27a12d300);
+        assert_eq!(cipher.decrypt_block(0x2587cae27a12d300), 0x33221100ddccbbaa);
+        assert_eq!(format!("{:?}", cipher), "Cipher { .. }");
+    }
+
+    #[test]
+    fn test_encrypt_block_as() {
+        let cipher = Cipher::new(KEY);
+
+        let bytes: [u8; 8] =
+            cipher.encrypt_block_as([0x33, 0x22, 0x11, 0x00, 0xdd, 0xcc, 0xbb, 0xaa]);
+        assert_eq!(bytes, [0x25, 0x87, 0xca, 0xe2, 0x7a, 0x12, 0xd3, 0x00]);
+        assert_eq!(
+            cipher.encrypt_block_as::<Block>(0x33221100ddccbbaa),
+            Block(0x2587cae27a12d300)
+        );
+        assert_eq!(
+            cipher.encrypt_block_as::<u64>(0x33221100ddccbbaa),
+            0x2587cae27a12d300
+        );
+
+        // Each output type decrypts back to the same plaintext.
+        assert_eq!(
+            cipher.decrypt_block_as::<[u8; 8]>(bytes),
+            [0x33, 0x22, 0x11, 0x00, 0xdd, 0xcc, 0xbb, 0xaa]
+        );
+        assert_eq!(
+            cipher.decrypt_block_as::<Block>(Block(0x2587cae27a12d300)),
+            Block(0x33221100ddccbbaa)
+        );
+    }
+
+    #[test]
+    fn test_encrypt_block_batch() {
+        let cipher = Cipher::new(KEY);
+        let blocks = [0x33221100ddccbbaa, 0, u64::MAX, 0x0123456789abcdef];
+
+        let batch = cipher.encrypt_block_batch(blocks);
+        assert_eq!(batch[0], 0x2587cae27a12d300);
+        for (&ciphertext, &block) in batch.iter().zip(blocks.iter()) {
+            assert_eq!(ciphertext, encrypt_block(block, KEY));
+        }
+
+        assert_eq!(cipher.encrypt_block_batch([0u64; 0]), [0u64; 0]);
+        assert_eq!(
+            cipher.encrypt_block_batch([0x33221100ddccbbaa]),
+            [0x2587cae27a12d300]
+        );
+    }
+
+    #[test]
+    fn test_encrypt_block_checked() {
+        let cipher = Cipher::new(KEY);
+
+        assert_eq!(
+            cipher.encrypt_block_checked(0x33221100ddccbbaa),
+            Ok(0x2587cae27a12d300)
+        );
+        for block in [0u64, 1, u64::MAX].iter() {
+            assert_eq!(
+                cipher.encrypt_block_checked(*block),
+                Ok(encrypt_block(*block, KEY))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_on_block() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&count);
+        let cipher = Cipher::new(KEY).with_on_block(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        // Neither a multiple of 4 (for `simd`) nor of 8 (for interleaving).
+        let mut blocks: Vec<u64> = (0..21).collect();
+        cipher.encrypt_blocks_inplace(&mut blocks);
+        assert_eq!(count.load(Ordering::Relaxed), 21);
+        assert_eq!(blocks[1], encrypt_block(1u64, KEY));
+
+        cipher.encrypt_blocks_interleaved(&mut blocks);
+        assert_eq!(count.load(Ordering::Relaxed), 42);
+
+        cipher.encrypt_block_batch([0u64; 4]);
+        assert_eq!(count.load(Ordering::Relaxed), 46);
+
+        // Single blocks don't count, and neither does a cipher without a callback.
+        cipher.encrypt_block(0u64);
+        Cipher::new(KEY).encrypt_blocks_inplace(&mut blocks);
+        assert_eq!(count.load(Ordering::Relaxed), 46);
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut blocks = vec![0u64; 10_000];
+            cipher.encrypt_par(&mut blocks);
+            assert_eq!(count.load(Ordering::Relaxed), 10_046);
+        }
+    }
+
+    #[test]
+    fn test_new_checked() {
+        assert!(Cipher::new_checked(KEY).is_ok());
+
+        // A weak key is only rejected by the checked constructor.
+        let weak = [0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd];
+        assert!(matches!(Cipher::new_checked(weak), Err(Error::WeakKey)));
+        assert!(matches!(Cipher::new_checked([0; 10]), Err(Error::WeakKey)));
+
+        let cipher = Cipher::new(weak);
+        assert_eq!(cipher.encrypt_block(0u64), encrypt_block(0u64, weak));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_encrypt_par() {
+        let cipher = Cipher::new(KEY);
+
+        // Several chunks' worth, and not a multiple of the chunk size.
+        let plaintext: Vec<u64> = (0..(3 * PAR_CHUNK as u64 + 1003))
+            .map(|i| i.wrapping_mul(0x9e3779b97f4a7c15))
+            .collect();
+
+        let mut blocks = plaintext.clone();
+        cipher.encrypt_par(&mut blocks);
+
+        for (ciphertext, &block) in blocks.iter().zip(plaintext.iter()) {
+            assert_eq!(*ciphertext, cipher.encrypt_block(block));
+        }
+    }
+
+    #[test]
+    fn test_with_key() {
+        let cipher = Cipher::new(KEY);
+        assert_eq!(cipher.key_bytes(), KEY);
+
+        let new_key = *b"0123456789";
+        let rekeyed = cipher.with_key(new_key);
+        let fresh = Cipher::new(new_key);
+
+        assert_eq!(rekeyed.key_bytes(), new_key);
+        for i in 0..16u64 {
+            assert_eq!(rekeyed.encrypt_block(i), fresh.encrypt_block(i));
+        }
+
+        // The original is unchanged.
+        assert_eq!(cipher.encrypt_block(0x33221100ddccbbaa), 0x2587cae27a12d300);
+    }
+
+    #[test]
+    fn test_default() {
+        let cipher = Cipher::default();
+
+        assert_eq!(cipher.encrypt_block(0x33221100ddccbbaa), 0x2587cae27a12d300);
+        assert_eq!(cipher.decrypt_block(0x2587cae27a12d300), 0x33221100ddccbbaa);
+    }
+
+    #[test]
+    fn test_cipher_matches_free_functions() {
+        // The precomputed schedule must give the same results as looking up
+        // each round's key bytes in the secret key.
+        let keys = [KEY, [0; 10], [0xff; 10], *b"0123456789"];
+        for &key in keys.iter() {
+            let cipher = Cipher::new(key);
+            for i in 0..64u64 {
+                let block = i.wrapping_mul(0x9e3779b97f4a7c15);
+                assert_eq!(cipher.encrypt_block(block), encrypt_block(block, key));
+                assert_eq!(cipher.decrypt_block(block), decrypt_block(block, key));
+            }
+        }
+    }
+
+    #[test]
+    fn test_encrypt_blocks_inplace() {
+        let cipher = Cipher::new(KEY);
+
+        // An odd length, so that there's a remainder after groups of four.
+        let plaintext: Vec<u64> = (0..1003u64)
+            .map(|i| i.wrapping_mul(0x9e3779b97f4a7c15))
+            .collect();
+
+        let mut blocks = plaintext.clone();
+        cipher.encrypt_blocks_inplace(&mut blocks);
+
+        for (ciphertext, &block) in blocks.iter().zip(plaintext.iter()) {
+            assert_eq!(*ciphertext, encrypt_block(block, KEY));
+        }
+    }
+
+    #[test]
+    fn test_encrypt_blocks_interleaved() {
+        let cipher = Cipher::new(KEY);
+
+        // Again an odd length, leaving a remainder after groups of eight.
+        let plaintext: Vec<u64> = (0..1003u64)
+            .map(|i| i.wrapping_mul(0x9e3779b97f4a7c15))
+            .collect();
+
+        let mut blocks = plaintext.clone();
+        cipher.encrypt_blocks_interleaved(&mut blocks);
+
+        for (ciphertext, &block) in blocks.iter().zip(plaintext.iter()) {
+            assert_eq!(*ciphertext, encrypt_block(block, KEY));
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! A simple self-describing container, with an explicit length instead of padding.
+//!
+//! `seal` produces, in order:
+//!
+//! * a random 8-byte IV, in the clear;
+//! * the CBC encryption, under that IV, of the data's length (8 bytes,
+//!   big-endian), then the data, then zero bytes up to a multiple of the block
+//!   size.
+//!
+//! Because the length is stored, `open` knows exactly where the data ends, and
+//! never has to guess from the padding: unlike PKCS#7, there's no ambiguity
+//! about data that happens to end in bytes that look like padding.
+//!
+//! **This is not authentication.** Like `framing`, it only detects some
+//! accidents (a wrong key almost always gives an impossible length). Anyone who
+//! can modify a container can make it decrypt to something else. If you need
+//! integrity against an adversary, use `aead`.
+//!
+//! **Nor is `open` safe to expose to an adversary.** Whether it succeeds
+//! depends on the decrypted length and the bytes after the data, and CBC lets
+//! an attacker who modifies the IV or a ciphertext block flip chosen bits of
+//! those. Every failure is the same `Error::InvalidContainer`, so the error
+//! doesn't say which check failed, but success or failure alone is enough: an
+//! attacker who can submit modified containers and tell whether each one
+//! opened (from a response, a log, or the time taken) has a padding oracle,
+//! and can decrypt containers without the key. Only open containers that
+//! nobody else could have modified, or use `aead`, which checks a MAC before
+//! decrypting anything.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::convert::TryInto;
+
+use super::cbc::{cbc_step_decrypt, cbc_step_encrypt};
+use super::{Error, BLOCK_SIZE};
+
+// The size of the length prefix, in bytes.
+const LENGTH_SIZE: usize = 8;
+
+// Seals the data under the given IV. `seal` is this, with a random IV.
+fn seal_with_iv(data: &[u8], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    let framed_len = LENGTH_SIZE + data.len();
+    let padded_len = framed_len.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+
+    let mut container = Vec::with_capacity(BLOCK_SIZE + padded_len);
+    container.extend_from_slice(&iv.to_be_bytes());
+    container.extend_from_slice(&(data.len() as u64).to_be_bytes());
+    container.extend_from_slice(data);
+    container.resize(BLOCK_SIZE + padded_len, 0);
+
+    let mut chain = iv.to_be_bytes();
+    for chunk in container[BLOCK_SIZE..].chunks_exact_mut(BLOCK_SIZE) {
+        cbc_step_encrypt(chunk.try_into().unwrap(), &mut chain, key.borrow());
+    }
+
+    container
+}
+
+/// Encrypts the given data into a container, under a fresh random IV.
+///
+/// See the module documentation for the format. The container is between 16
+/// and 23 bytes longer than the data.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt
+/// * `key` - The secret key to encrypt with
+pub fn seal(data: &[u8], key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    seal_with_iv(data, rand::random(), key)
+}
+
+/// Decrypts a container produced by `seal`, returning exactly the original data.
+///
+/// Returns `Error::InvalidContainer` if the container isn't a whole number of
+/// blocks, is too short to hold the IV and the length, has a decrypted length
+/// that doesn't match its size, or has nonzero bytes after the data (which
+/// usually means the wrong key). It's the same error in every case, but see the
+/// module documentation before exposing this to anyone who can modify
+/// containers.
+///
+/// # Arguments
+///
+/// * `container` - The container to decrypt
+/// * `key` - The secret key to decrypt with
+pub fn open(container: &[u8], key: impl Borrow<[u8; 10]>) -> Result<Vec<u8>, Error> {
+    if container.len() < BLOCK_SIZE + LENGTH_SIZE || !container.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(Error::InvalidContainer);
+    }
+
+    let (iv, ciphertext) = container.split_at(BLOCK_SIZE);
+    let mut chain: [u8; BLOCK_SIZE] = iv.try_into().unwrap();
+
+    let mut plaintext = ciphertext.to_vec();
+    for chunk in plaintext.chunks_exact_mut(BLOCK_SIZE) {
+        cbc_step_decrypt(chunk.try_into().unwrap(), &mut chain, key.borrow());
+    }
+
+    // The length has to account for everything but the last few (zero) bytes.
+    // Both checks always run, and fail together, so that neither can be told
+    // apart from the other.
+    let length = u64::from_be_bytes(plaintext[..LENGTH_SIZE].try_into().unwrap());
+    let room = (plaintext.len() - LENGTH_SIZE) as u64;
+    let length_ok = length <= room && room - length < BLOCK_SIZE as u64;
+
+    let end = LENGTH_SIZE + length.min(room) as usize;
+    let zeroes_ok = plaintext[end..].iter().fold(0, |acc, &byte| acc | byte) == 0;
+
+    if !(length_ok & zeroes_ok) {
+        return Err(Error::InvalidContainer);
+    }
+
+    plaintext.truncate(end);
+    plaintext.drain(..LENGTH_SIZE);
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..=24 {
+            let data: Vec<u8> = (0..len as u8).collect();
+            let container = seal(&data, KEY);
+
+            // The IV, the length, the data, and then up to 7 bytes of zeroes.
+            assert_eq!(
+                container.len(),
+                BLOCK_SIZE + (LENGTH_SIZE + len).div_ceil(BLOCK_SIZE) * BLOCK_SIZE
+            );
+            assert_eq!(open(&container, KEY), Ok(data));
+        }
+    }
+
+    #[test]
+    fn test_padding_lookalikes() {
+        // Each of these ends in what PKCS#7 would take for padding (or, for
+        // the zeroes, what this format uses), and comes back exactly.
+        let lookalikes: [&[u8]; 4] = [b"\x01", b"abcdefg\x01", &[8; 8], &[0; 5]];
+        for data in lookalikes.iter() {
+            assert_eq!(open(&seal(data, KEY), KEY).as_deref(), Ok(*data));
+        }
+    }
+
+    #[test]
+    fn test_seal_with_iv() {
+        let container = seal_with_iv(b"", IV, KEY);
+        assert_eq!(container[..BLOCK_SIZE], IV.to_be_bytes());
+        assert_eq!(container.len(), 2 * BLOCK_SIZE);
+
+        // Random IVs (almost surely) differ, and so do the ciphertexts.
+        assert_ne!(seal(b"same", KEY), seal(b"same", KEY));
+    }
+
+    #[test]
+    fn test_open_invalid() {
+        let container = seal_with_iv(b"attack at dawn", IV, KEY);
+
+        // Every failure is the same error, whatever went wrong.
+        assert_eq!(open(&container[1..], KEY), Err(Error::InvalidContainer));
+        assert_eq!(
+            open(&container[..BLOCK_SIZE], KEY),
+            Err(Error::InvalidContainer)
+        );
+
+        // The wrong key decrypts to a nonsensical length.
+        let wrong_key = [0x11; 10];
+        assert_eq!(open(&container, wrong_key), Err(Error::InvalidContainer));
+
+        // Dropping the last block leaves too little room for the stated length.
+        assert_eq!(
+            open(&container[..container.len() - BLOCK_SIZE], KEY),
+            Err(Error::InvalidContainer)
+        );
+
+        // Flipping a bit of the IV flips the same bit of the decrypted length,
+        // and flipping one in the second-to-last block flips one of the bytes
+        // after the data: both fail, the same way.
+        let mut length_flipped = container.clone();
+        length_flipped[0] ^= 0x80;
+        assert_eq!(open(&length_flipped, KEY), Err(Error::InvalidContainer));
+
+        let mut zeroes_flipped = container.clone();
+        zeroes_flipped[container.len() - BLOCK_SIZE - 1] ^= 0x01;
+        assert_eq!(open(&zeroes_flipped, KEY), Err(Error::InvalidContainer));
+    }
+}
@@ -0,0 +1,651 @@
+//! Counter (CTR) mode.
+//!
+//! CTR mode turns Skipjack into a stream cipher: the keystream is the encryption
+//! of successive counter blocks, beginning with the nonce, and is XOR'ed with the
+//! data. Because XOR is its own inverse, encryption and decryption are the same
+//! operation.
+//!
+//! **Never use the same nonce twice with the same key.** Doing so reuses the
+//! keystream, which reveals the XOR of the two plaintexts.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+
+use super::{encrypt_block, Cipher, Error, BLOCK_SIZE};
+
+/// Applies the CTR keystream for the given nonce and key to `data`, in place.
+///
+/// The `i`th block of keystream is the encryption of `nonce + i` (wrapping at
+/// `u64::MAX`), in big-endian byte order. `data` does not need to be a multiple
+/// of the block size; any unused keystream from the final block is discarded.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt or decrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+pub fn apply_keystream(data: &mut [u8], nonce: u64, key: impl Borrow<[u8; 10]>) {
+    Cipher::new(key).apply_ctr_keystream(data, nonce);
+}
+
+/// Applies the CTR keystream for the given nonce and key to a `BytesMut`, in place.
+///
+/// This is `apply_keystream`, for buffers from the `bytes` crate (as used by
+/// tokio and friends): the data is modified where it is, without a copy.
+///
+/// # Arguments
+///
+/// * `buf` - The data to encrypt or decrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "bytes")]
+pub fn apply_keystream_bytes(buf: &mut bytes::BytesMut, nonce: u64, key: impl Borrow<[u8; 10]>) {
+    apply_keystream(buf, nonce, key);
+}
+
+/// Encrypts `data` in CTR mode, returning the ciphertext.
+///
+/// This is the allocating counterpart to `apply_keystream`, for data that you
+/// don't own (or don't want to modify): it's exactly `apply_keystream` on a copy.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "alloc")]
+pub fn encrypt(data: &[u8], nonce: u64, key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    let mut output = data.to_vec();
+    apply_keystream(&mut output, nonce, key);
+
+    output
+}
+
+/// Decrypts `data` in CTR mode, returning the plaintext.
+///
+/// In CTR mode, decryption is the same operation as encryption; this is just
+/// `encrypt` under a name that reads better at the call site.
+///
+/// # Arguments
+///
+/// * `data` - The data to decrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "alloc")]
+pub fn decrypt(data: &[u8], nonce: u64, key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    encrypt(data, nonce, key)
+}
+
+/// Encrypts (or decrypts) `data` in CTR mode, writing the result into `out`.
+///
+/// This is `apply_keystream` for when the input can't be modified in place,
+/// and doesn't allocate. Only the first `data.len()` bytes of `out` are written.
+/// Returns `Error::BufferTooSmall` if `out` is shorter than `data`.
+///
+/// # Arguments
+///
+/// * `out` - The buffer to write the result into
+/// * `data` - The data to encrypt or decrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+pub fn encrypt_ctr_into(
+    out: &mut [u8],
+    data: &[u8],
+    nonce: u64,
+    key: impl Borrow<[u8; 10]>,
+) -> Result<(), Error> {
+    if out.len() < data.len() {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let keystream = Keystream::new(nonce, key);
+    for ((o, d), k) in out.iter_mut().zip(data.iter()).zip(keystream) {
+        *o = d ^ k;
+    }
+
+    Ok(())
+}
+
+impl<M> Cipher<M> {
+    /// Applies the CTR keystream for the given nonce to `data`, in place, like
+    /// `ctr::apply_keystream`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to encrypt or decrypt
+    /// * `nonce` - The initial counter value
+    pub fn apply_ctr_keystream(&self, data: &mut [u8], nonce: u64) {
+        let mut counter = nonce;
+
+        for chunk in data.chunks_mut(BLOCK_SIZE) {
+            let keystream = self.encrypt_block(counter).to_be_bytes();
+            for (byte, k) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= k;
+            }
+
+            counter = counter.wrapping_add(1);
+        }
+    }
+}
+
+// The number of keystream blocks in each unit of work handed to rayon.
+#[cfg(feature = "rayon")]
+const PAR_CHUNK_BLOCKS: usize = 4096;
+
+/// Writes the CTR keystream for the given nonce and key into `out`, in parallel.
+///
+/// The result is exactly what `Keystream` yields (and what `apply_keystream`
+/// XORs with its input), but computed across rayon's thread pool: each block
+/// of keystream depends only on its own counter, `nonce + i` for the `i`th
+/// block, so blocks can be generated in any order. `out` is split on block
+/// boundaries, so each chunk starts at a known counter; a final partial block
+/// gets the start of its keystream block.
+///
+/// # Arguments
+///
+/// * `out` - The buffer to fill with keystream
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "rayon")]
+pub fn generate_keystream_par(out: &mut [u8], nonce: u64, key: impl Borrow<[u8; 10]>) {
+    for_each_keystream_block_par(out, nonce, key, |out, keystream| {
+        out.copy_from_slice(&keystream[..out.len()]);
+    });
+}
+
+/// Applies the CTR keystream for the given nonce and key to `data`, in place,
+/// in parallel.
+///
+/// This is `apply_keystream`, with the keystream generated and XOR'ed in
+/// across rayon's thread pool as in `generate_keystream_par`.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt or decrypt
+/// * `nonce` - The initial counter value
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "rayon")]
+pub fn apply_keystream_par(data: &mut [u8], nonce: u64, key: impl Borrow<[u8; 10]>) {
+    for_each_keystream_block_par(data, nonce, key, |data, keystream| {
+        for (byte, k) in data.iter_mut().zip(keystream.iter()) {
+            *byte ^= k;
+        }
+    });
+}
+
+// Calls `f` with each block of `data` (the last of which may be partial) and
+// the matching block of keystream, in parallel.
+#[cfg(feature = "rayon")]
+fn for_each_keystream_block_par<F>(data: &mut [u8], nonce: u64, key: impl Borrow<[u8; 10]>, f: F)
+where
+    F: Fn(&mut [u8], &[u8; BLOCK_SIZE]) + Sync,
+{
+    use rayon::prelude::*;
+
+    let cipher = Cipher::new(key);
+
+    data.par_chunks_mut(PAR_CHUNK_BLOCKS * BLOCK_SIZE)
+        .enumerate()
+        .for_each(|(chunk_index, chunk)| {
+            // Every chunk but the last is a whole number of blocks, so the
+            // `j`th block of chunk `i` is block `i * PAR_CHUNK_BLOCKS + j`.
+            let first = nonce.wrapping_add((chunk_index * PAR_CHUNK_BLOCKS) as u64);
+
+            for (j, block) in chunk.chunks_mut(BLOCK_SIZE).enumerate() {
+                let counter = first.wrapping_add(j as u64);
+                f(block, &cipher.encrypt_block(counter).to_be_bytes());
+            }
+        });
+}
+
+/// Derives a per-message nonce from a per-session salt and a message counter.
+///
+/// The nonce is the Skipjack encryption of `message_counter` under a key made
+/// from `session_salt`: its 8 big-endian bytes, followed by two zero bytes.
+/// Because encryption under a fixed key is a permutation, distinct counters
+/// always produce distinct nonces, and without the salt the nonces can't be
+/// predicted from the counters.
+///
+/// The salt should be random, and **must** change whenever the CTR key
+/// does. The counter must never repeat within a session.
+///
+/// Note that each nonce is only the *starting* counter for its message: a
+/// message of `n` blocks uses counters `nonce` through `nonce + n - 1`, and two
+/// messages whose ranges overlap still share keystream. With random-looking
+/// 64-bit nonces that's unlikely for modest message sizes, but not impossible.
+///
+/// # Arguments
+///
+/// * `session_salt` - A random value, unique to the session (and the key)
+/// * `message_counter` - A counter, unique to each message in the session
+pub fn derive_nonce(session_salt: u64, message_counter: u64) -> u64 {
+    let mut salt_key = [0u8; 10];
+    salt_key[..8].copy_from_slice(&session_salt.to_be_bytes());
+
+    encrypt_block(message_counter, salt_key)
+}
+
+/// Encrypts `data` in CTR mode under a fresh random nonce, returning the nonce
+/// and the ciphertext.
+///
+/// The nonce comes from the operating system's random number generator (by way
+/// of `rand`), so there's no nonce for the caller to get wrong; it isn't secret,
+/// and must be sent or stored alongside the ciphertext for `decrypt_with_nonce`.
+///
+/// Random nonces are only unique with high probability, and 64 bits isn't much.
+/// By the birthday bound, after `n` messages the chance that two share a nonce
+/// is about `n^2 / 2^65`: one in a billion after about 190,000 messages, and
+/// roughly even odds after 2^32. And since a message of `b` blocks uses
+/// the `b` counters starting at its nonce, what actually matters is whether two
+/// messages' *ranges* overlap, which is about `b` times likelier. Change keys
+/// long before either gets close.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "rng")]
+pub fn encrypt_with_random_nonce(data: &[u8], key: impl Borrow<[u8; 10]>) -> (u64, Vec<u8>) {
+    let nonce = rand::random();
+
+    (nonce, encrypt(data, nonce, key))
+}
+
+/// Decrypts `data` that was encrypted by `encrypt_with_random_nonce`, returning
+/// the plaintext.
+///
+/// # Arguments
+///
+/// * `nonce` - The nonce returned by `encrypt_with_random_nonce`
+/// * `data` - The data to decrypt
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "rng")]
+pub fn decrypt_with_nonce(nonce: u64, data: &[u8], key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    decrypt(data, nonce, key)
+}
+
+/// Hands out non-overlapping ranges of CTR counters, for encrypting many
+/// messages under one key.
+///
+/// A message of `n` blocks uses the `n` counters starting at its nonce, so two
+/// messages under the same key must not only have different nonces, but
+/// ranges that don't overlap. An allocator keeps track of where the last range
+/// ended: each call to `reserve` returns the start of a fresh range, to use as
+/// that message's nonce, and moves past it. Ranges never wrap around past
+/// `u64::MAX`; once the counters run out, `reserve` fails, and it's time for a
+/// new key.
+///
+/// This deliberately isn't `Clone`: two copies of an allocator would hand out
+/// the same ranges. Nor does it remember anything across restarts, so a
+/// long-lived key needs its position saved (see `position`) and restored with
+/// `starting_at`.
+#[derive(Debug, Default)]
+pub struct CounterAllocator {
+    // The next counter to hand out. This is 2^64 once every counter has been
+    // handed out, which is why it's wider than a counter.
+    next: u128,
+}
+
+impl CounterAllocator {
+    /// Creates a new allocator, whose first range starts at counter 0.
+    pub fn new() -> Self {
+        CounterAllocator::starting_at(0)
+    }
+
+    /// Creates a new allocator, whose first range starts at the given counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The first counter to hand out
+    pub fn starting_at(start: u64) -> Self {
+        CounterAllocator {
+            next: start as u128,
+        }
+    }
+
+    /// Reserves a range of `num_blocks` counters, returning the first one.
+    ///
+    /// The range is the counters from the returned value up to (but not
+    /// including) that value plus `num_blocks`, and no other call to `reserve`
+    /// on this allocator returns an overlapping one. For a message of `len`
+    /// bytes, `num_blocks` is `len` divided by the block size, rounded up.
+    ///
+    /// Returns `Error::EmptyReservation` if `num_blocks` is 0: an empty range
+    /// has no counters in it, so its start would be a counter the next call
+    /// hands out too, and using it as a nonce would reuse the keystream. (For
+    /// an empty message, there's nothing to encrypt, and no counter is needed.)
+    /// Returns `Error::CounterExhausted`, without reserving anything, if the
+    /// range would run past `u64::MAX`.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_blocks` - The number of counters to reserve
+    pub fn reserve(&mut self, num_blocks: u64) -> Result<u64, Error> {
+        if num_blocks == 0 {
+            return Err(Error::EmptyReservation);
+        }
+
+        let end = self.next + num_blocks as u128;
+        if end > 1 << 64 || self.next == 1 << 64 {
+            return Err(Error::CounterExhausted);
+        }
+
+        let start = self.next as u64;
+        self.next = end;
+
+        Ok(start)
+    }
+
+    /// Returns the next counter that `reserve` will hand out, or `None` if
+    /// every counter has been handed out.
+    pub fn position(&self) -> Option<u64> {
+        if self.next == 1 << 64 {
+            None
+        } else {
+            Some(self.next as u64)
+        }
+    }
+}
+
+/// An endless iterator over the bytes of the CTR keystream.
+///
+/// This yields exactly the bytes that `apply_keystream` XORs with its input:
+/// the 8 big-endian bytes of the encryption of `nonce`, then of `nonce + 1`,
+/// and so on. The counter wraps around to 0 after `u64::MAX`.
+#[derive(Clone)]
+pub struct Keystream {
+    cipher: Cipher,
+    counter: u64,
+    block: [u8; BLOCK_SIZE],
+    position: usize,
+}
+
+impl Keystream {
+    /// Creates a new keystream for the given nonce and key.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The initial counter value
+    /// * `key` - The secret key to generate the keystream with
+    pub fn new(nonce: u64, key: impl Borrow<[u8; 10]>) -> Self {
+        // We start with our position at the end of an (empty) block, so that
+        // the first call to `next` encrypts the nonce.
+        Keystream {
+            cipher: Cipher::new(key),
+            counter: nonce,
+            block: [0; BLOCK_SIZE],
+            position: BLOCK_SIZE,
+        }
+    }
+}
+
+impl Iterator for Keystream {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.position == BLOCK_SIZE {
+            self.block = self.cipher.encrypt_block(self.counter).to_be_bytes();
+            self.counter = self.counter.wrapping_add(1);
+            self.position = 0;
+        }
+
+        let byte = self.block[self.position];
+        self.position += 1;
+
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl fmt::Debug for Keystream {
+    // Never include the key (or anything derived from it) in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keystream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_apply_keystream() {
+        let plaintext = b"an odd number of bytes, not a multiple of eight";
+        let nonce = 0x33221100ddccbbaa;
+
+        let mut data = plaintext.to_vec();
+        apply_keystream(&mut data, nonce, KEY);
+
+        // The first block of keystream is just the encrypted nonce.
+        assert_eq!(data[0] ^ plaintext[0], 0x25);
+        assert_ne!(&data[..], &plaintext[..]);
+
+        apply_keystream(&mut data, nonce, KEY);
+        assert_eq!(&data[..], &plaintext[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn test_apply_keystream_bytes() {
+        let plaintext = b"an odd number of bytes, not a multiple of eight";
+        let nonce = 0x33221100ddccbbaa;
+
+        let mut buf = bytes::BytesMut::from(&plaintext[..]);
+        apply_keystream_bytes(&mut buf, nonce, KEY);
+
+        let mut expected = plaintext.to_vec();
+        apply_keystream(&mut expected, nonce, KEY);
+        assert_eq!(&buf[..], &expected[..]);
+
+        apply_keystream_bytes(&mut buf, nonce, KEY);
+        assert_eq!(&buf[..], &plaintext[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "rng")]
+    fn test_random_nonce() {
+        let plaintext = b"a message whose nonce we never chose";
+
+        let (nonce, ciphertext) = encrypt_with_random_nonce(plaintext, KEY);
+        assert_eq!(ciphertext, encrypt(plaintext, nonce, KEY));
+        assert_eq!(decrypt_with_nonce(nonce, &ciphertext, KEY), plaintext);
+
+        // Two encryptions of the same message (almost surely) get different nonces.
+        let (other_nonce, other_ciphertext) = encrypt_with_random_nonce(plaintext, KEY);
+        assert_ne!(nonce, other_nonce);
+        assert_ne!(ciphertext, other_ciphertext);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_encrypt_decrypt() {
+        let plaintext = b"borrowed, not owned";
+        let nonce = 0x33221100ddccbbaa;
+
+        let ciphertext = encrypt(plaintext, nonce, KEY);
+
+        let mut expected = plaintext.to_vec();
+        apply_keystream(&mut expected, nonce, KEY);
+        assert_eq!(ciphertext, expected);
+
+        assert_eq!(decrypt(&ciphertext, nonce, KEY), plaintext);
+    }
+
+    #[test]
+    fn test_encrypt_ctr_into() {
+        let plaintext = b"fits in a fixed buffer";
+        let nonce = 7;
+
+        let mut ciphertext = [0u8; 32];
+        assert_eq!(
+            encrypt_ctr_into(&mut ciphertext, plaintext, nonce, KEY),
+            Ok(())
+        );
+
+        // The result matches the in-place API, and the rest of `out` is untouched.
+        let mut expected = plaintext.to_vec();
+        apply_keystream(&mut expected, nonce, KEY);
+        assert_eq!(&ciphertext[..plaintext.len()], &expected[..]);
+        assert_eq!(&ciphertext[plaintext.len()..], &[0u8; 10][..]);
+
+        let mut decrypted = [0u8; 22];
+        assert_eq!(
+            encrypt_ctr_into(&mut decrypted, &ciphertext[..plaintext.len()], nonce, KEY),
+            Ok(())
+        );
+        assert_eq!(&decrypted, plaintext);
+
+        let mut small = [0u8; 21];
+        assert_eq!(
+            encrypt_ctr_into(&mut small, plaintext, nonce, KEY),
+            Err(Error::BufferTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_cipher_methods() {
+        let plaintext = b"an odd number of bytes, not a multiple of eight";
+        let cipher = Cipher::new(KEY);
+
+        // The keystream matches `Keystream`, including across the counter wrapping.
+        let nonce = u64::MAX - 1;
+        let mut data = plaintext.to_vec();
+        cipher.apply_ctr_keystream(&mut data, nonce);
+
+        let expected: Vec<u8> = plaintext
+            .iter()
+            .zip(Keystream::new(nonce, KEY))
+            .map(|(p, k)| p ^ k)
+            .collect();
+        assert_eq!(data, expected);
+
+        cipher.apply_ctr_keystream(&mut data, nonce);
+        assert_eq!(&data[..], &plaintext[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_keystream_par() {
+        // A few parallel chunks and then some, ending partway through a block,
+        // with a counter that wraps partway through the second chunk.
+        let len = (3 * PAR_CHUNK_BLOCKS + 10) * BLOCK_SIZE + 5;
+        let nonce = u64::MAX - PAR_CHUNK_BLOCKS as u64 - 100;
+
+        let mut parallel = vec![0xaa; len];
+        generate_keystream_par(&mut parallel, nonce, KEY);
+
+        let serial: Vec<u8> = Keystream::new(nonce, KEY).take(len).collect();
+        assert!(parallel == serial);
+
+        let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let mut expected = data.clone();
+        apply_keystream(&mut expected, nonce, KEY);
+
+        let mut applied = data;
+        apply_keystream_par(&mut applied, nonce, KEY);
+        assert!(applied == expected);
+    }
+
+    #[test]
+    fn test_derive_nonce() {
+        use std::collections::HashSet;
+
+        let salt = 0x5eed5eed5eed5eed;
+
+        let nonces: HashSet<u64> = (0..1000).map(|i| derive_nonce(salt, i)).collect();
+        assert_eq!(nonces.len(), 1000);
+
+        // The same session and counter always yield the same nonce.
+        assert_eq!(derive_nonce(salt, 1), derive_nonce(salt, 1));
+
+        // The same counter in a different session yields a different nonce.
+        assert_ne!(derive_nonce(salt, 1), derive_nonce(salt + 1, 1));
+    }
+
+    #[test]
+    fn test_counter_allocator() {
+        let mut allocator = CounterAllocator::new();
+        let sizes = [3u64, 1, 1000, 2];
+
+        // Consecutive ranges, none overlapping.
+        let starts: Vec<u64> = sizes
+            .iter()
+            .map(|&n| allocator.reserve(n).unwrap())
+            .collect();
+        assert_eq!(starts, [0, 3, 4, 1004]);
+        for (i, (&a, &n)) in starts.iter().zip(sizes.iter()).enumerate() {
+            for (&b, &m) in starts.iter().zip(sizes.iter()).skip(i + 1) {
+                assert!(a + n <= b || b + m <= a);
+            }
+        }
+        assert_eq!(allocator.position(), Some(1006));
+
+        // An empty range is rejected, and reserves nothing.
+        assert_eq!(allocator.reserve(0), Err(Error::EmptyReservation));
+        assert_eq!(allocator.position(), Some(1006));
+        assert_eq!(allocator.reserve(1), Ok(1006));
+
+        // Each reserved range encrypts with its own keystream.
+        let mut first = [0u8; 24];
+        let mut second = [0u8; 24];
+        apply_keystream(&mut first, starts[0], KEY);
+        apply_keystream(&mut second, starts[1], KEY);
+        assert_eq!(
+            first[8..16],
+            Keystream::new(1, KEY).take(8).collect::<Vec<_>>()[..]
+        );
+        assert_ne!(first[..8], second[..8]);
+    }
+
+    #[test]
+    fn test_counter_allocator_exhausted() {
+        let mut allocator = CounterAllocator::starting_at(u64::MAX - 9);
+
+        // A range that would run past `u64::MAX` fails, and reserves nothing.
+        assert_eq!(allocator.reserve(11), Err(Error::CounterExhausted));
+        assert_eq!(allocator.reserve(u64::MAX), Err(Error::CounterExhausted));
+        assert_eq!(allocator.position(), Some(u64::MAX - 9));
+
+        // A range that ends exactly at `u64::MAX` is fine, and uses everything up.
+        assert_eq!(allocator.reserve(4), Ok(u64::MAX - 9));
+        assert_eq!(allocator.reserve(6), Ok(u64::MAX - 5));
+        assert_eq!(allocator.position(), None);
+        assert_eq!(allocator.reserve(1), Err(Error::CounterExhausted));
+        assert_eq!(allocator.reserve(0), Err(Error::EmptyReservation));
+
+        // The whole counter space is one range.
+        let mut allocator = CounterAllocator::new();
+        assert_eq!(allocator.reserve(u64::MAX), Ok(0));
+        assert_eq!(allocator.reserve(1), Ok(u64::MAX));
+        assert_eq!(allocator.reserve(1), Err(Error::CounterExhausted));
+    }
+
+    #[test]
+    fn test_keystream() {
+        let nonce = 0x0123456789abcdef;
+        let keystream: Vec<u8> = Keystream::new(nonce, KEY).take(16).collect();
+
+        assert_eq!(keystream[..8], encrypt_block(nonce, KEY).to_be_bytes());
+        assert_eq!(keystream[8..], encrypt_block(nonce + 1, KEY).to_be_bytes());
+
+        // The key never shows up in debug output.
+        assert_eq!(
+            format!("{:?}", Keystream::new(nonce, KEY)),
+            "Keystream { .. }"
+        );
+    }
+
+    #[test]
+    fn test_keystream_wraps() {
+        let keystream: Vec<u8> = Keystream::new(u64::MAX, KEY).skip(8).take(8).collect();
+
+        assert_eq!(keystream[..], encrypt_block(0, KEY).to_be_bytes());
+    }
+}
@@ -0,0 +1,12 @@
+/// Whether a mode of operation should encrypt or decrypt.
+///
+/// Each mode's `transform` function takes one of these, for callers that choose
+/// a direction at runtime. The dedicated `encrypt`/`decrypt` functions are
+/// equivalent, and are usually clearer when the direction is fixed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Turn plaintext into ciphertext.
+    Encrypt,
+    /// Turn ciphertext back into plaintext.
+    Decrypt,
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+
+/// Errors that can occur when using this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The input was not exactly one block (8 bytes) long.
+    InvalidBlockLength,
+    /// An authentication tag did not match the data it was supposed to authenticate.
+    AuthenticationFailed,
+    /// Decrypted data was rejected by a caller-supplied sanity check.
+    DecryptionSanityFailed,
+    /// The input was not a multiple of the block size (8 bytes).
+    BufferNotBlockAligned,
+    /// Decrypted data did not end with valid padding.
+    InvalidPadding,
+    /// An output buffer was too small to hold the result.
+    BufferTooSmall,
+    /// An integer was too large to be an 80-bit key.
+    KeyOutOfRange,
+    /// A key was not exactly 10 bytes long.
+    InvalidKeyLength,
+    /// Text that should have been hex bytes wasn't.
+    InvalidHex,
+    /// Hex decoded to the wrong number of bytes.
+    InvalidHexLength,
+    /// A CRC did not match the data it was computed over.
+    CrcMismatch,
+    /// A MAC tag length was outside the supported range (1 to 8 bytes).
+    InvalidTagLength,
+    /// A key was rejected because `is_weak_key` flagged it.
+    WeakKey,
+    /// Two buffers that should have been the same length weren't.
+    LengthMismatch,
+    /// A self-test found that the cipher isn't working as it should.
+    SelfTestFailed,
+    /// An IV was used a second time within a session.
+    IvReused,
+    /// The cipher broke one of its own internal invariants. This is a bug.
+    InternalInvariant,
+    /// There weren't enough CTR counter values left to reserve a range.
+    CounterExhausted,
+    /// A CTR counter range of zero blocks was requested.
+    EmptyReservation,
+    /// A key split into shares (see `encrypt_block_shared`) had no shares.
+    NoKeyShares,
+    /// A nonce was a value that's reserved, like `wc_mac`'s nonce 0.
+    ReservedNonce,
+    /// A container (see `container`) was malformed, or opened with the wrong key.
+    InvalidContainer,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidBlockLength => write!(f, "input is not exactly one block (8 bytes)"),
+            Error::AuthenticationFailed => write!(f, "authentication failed"),
+            Error::BufferNotBlockAligned => {
+                write!(f, "input is not a multiple of the block size (8 bytes)")
+            }
+            Error::InvalidPadding => write!(f, "invalid padding"),
+            Error::BufferTooSmall => write!(f, "output buffer is too small"),
+            Error::KeyOutOfRange => write!(f, "value does not fit in an 80-bit key"),
+            Error::InvalidKeyLength => write!(f, "key is not exactly 10 bytes"),
+            Error::InvalidHex => write!(f, "invalid hex"),
+            Error::InvalidHexLength => write!(f, "hex decodes to the wrong number of bytes"),
+            Error::CrcMismatch => write!(f, "CRC mismatch (corrupted data or wrong key?)"),
+            Error::InvalidTagLength => write!(f, "tag length must be between 1 and 8 bytes"),
+            Error::WeakKey => write!(f, "key is weak (a repeating pattern?)"),
+            Error::LengthMismatch => write!(f, "input and output buffers differ in length"),
+            Error::SelfTestFailed => write!(f, "self-test failed (corrupted implementation?)"),
+            Error::IvReused => write!(f, "IV was already used in this session"),
+            Error::InternalInvariant => write!(f, "internal invariant violated (this is a bug)"),
+            Error::CounterExhausted => write!(f, "CTR counter space exhausted"),
+            Error::EmptyReservation => write!(f, "can't reserve zero CTR counters"),
+            Error::NoKeyShares => write!(f, "a shared key needs at least one share"),
+            Error::ReservedNonce => write!(f, "nonce is reserved"),
+            Error::InvalidContainer => {
+                write!(f, "invalid container (corrupted data or wrong key?)")
+            }
+            Error::DecryptionSanityFailed => {
+                write!(f, "decrypted data failed sanity check (wrong key?)")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        // `Display` is available with or without `std`.
+        assert_eq!(Error::InvalidPadding.to_string(), "invalid padding");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_boxed_std_error() {
+        fn fails() -> Result<(), Box<dyn std::error::Error>> {
+            Err(Error::InvalidHex)?;
+            Ok(())
+        }
+
+        let err = fails().unwrap_err();
+        assert_eq!(err.to_string(), "invalid hex");
+        assert_eq!(err.downcast_ref::<Error>(), Some(&Error::InvalidHex));
+    }
+}
@@ -0,0 +1,148 @@
+//! Fault injection, for studying differential fault analysis.
+//!
+//! A differential fault attack compares the correct ciphertext for a block with
+//! a "faulty" one, produced by disturbing the cipher's state partway through
+//! (in hardware, with a voltage glitch or a laser). `encrypt_block_with_fault`
+//! produces such faulty ciphertexts in software.
+
+use core::borrow::Borrow;
+
+use super::{block_to_words, rule_a, rule_b, uses_rule_a, words_to_block, IntoBlock, ROUNDS};
+
+/// A fault to inject into the cipher's state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Fault {
+    /// The round after which to inject the fault, from 1 to 32. A round of 0
+    /// injects the fault before the first round, i.e. into the plaintext.
+    pub round: u16,
+    /// The word to inject the fault into, from 0 (Word 1) to 3 (Word 4).
+    pub word: usize,
+    /// The mask to XOR into the word. A mask of 0 injects no fault at all.
+    pub xor_mask: u16,
+}
+
+/// Encrypts the given 64-bit block, injecting a fault into the state.
+///
+/// With a zero `xor_mask`, this is identical to `encrypt_block`.
+///
+/// # Arguments
+///
+/// * `block` - The block to encrypt, as a `u64`, `[u8; 8]`, or `Block`
+/// * `key` - The secret key to encrypt with
+/// * `fault` - The fault to inject
+///
+/// # Panics
+///
+/// Panics if `fault.round` is greater than 32 or `fault.word` is greater than 3.
+pub fn encrypt_block_with_fault(
+    block: impl IntoBlock,
+    key: impl Borrow<[u8; 10]>,
+    fault: Fault,
+) -> u64 {
+    let key = key.borrow();
+
+    assert!(
+        fault.round as usize <= ROUNDS,
+        "fault round must be at most 32"
+    );
+    assert!(fault.word < 4, "fault word must be 0 to 3");
+
+    let mut words = block_to_words(block.into_block());
+    if fault.round == 0 {
+        words[fault.word] ^= fault.xor_mask;
+    }
+
+    // Like `encrypt_block_rounds`, each rule increments the counter, so the
+    // round we just finished is always `counter - 1`.
+    let mut counter = 1;
+    while counter as usize <= ROUNDS {
+        if uses_rule_a(counter) {
+            rule_a(&mut words, &mut counter, key);
+        } else {
+            rule_b(&mut words, &mut counter, key);
+        }
+
+        if counter - 1 == fault.round {
+            words[fault.word] ^= fault.xor_mask;
+        }
+    }
+
+    words_to_block(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decrypt_block, encrypt_block};
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_zero_mask() {
+        for round in 0..=32 {
+            for word in 0..4 {
+                let fault = Fault {
+                    round,
+                    word,
+                    xor_mask: 0,
+                };
+                assert_eq!(
+                    encrypt_block_with_fault(0x33221100ddccbbaa, KEY, fault),
+                    0x2587cae27a12d300
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fault() {
+        let plaintext = 0x33221100ddccbbaa;
+
+        // A fault before the first round is a fault in the plaintext.
+        let fault = Fault {
+            round: 0,
+            word: 1,
+            xor_mask: 0x0100,
+        };
+        assert_eq!(
+            encrypt_block_with_fault(plaintext, KEY, fault),
+            encrypt_block(plaintext ^ 0x0000_0100_0000_0000, KEY)
+        );
+
+        // A fault after the last round is a fault in the ciphertext.
+        for word in 0..4 {
+            let fault = Fault {
+                round: 32,
+                word,
+                xor_mask: 0xffff,
+            };
+            let shift = 48 - 16 * word;
+            assert_eq!(
+                encrypt_block_with_fault(plaintext, KEY, fault),
+                0x2587cae27a12d300 ^ (0xffff << shift)
+            );
+        }
+
+        // A fault in the middle produces a ciphertext that doesn't decrypt to
+        // the plaintext.
+        let fault = Fault {
+            round: 13,
+            word: 2,
+            xor_mask: 0x0001,
+        };
+        let faulty = encrypt_block_with_fault(plaintext, KEY, fault);
+        assert_ne!(faulty, 0x2587cae27a12d300);
+        assert_ne!(decrypt_block(faulty, KEY), plaintext);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fault_out_of_range() {
+        let fault = Fault {
+            round: 33,
+            word: 0,
+            xor_mask: 1,
+        };
+        encrypt_block_with_fault(0u64, KEY, fault);
+    }
+}
@@ -0,0 +1,125 @@
+//! A C interface to Skipjack.
+//!
+//! These functions are exported unmangled, with the C calling convention, so
+//! that C (or anything else that can call C) can link against this crate.
+//! They're the only truly `unsafe` code in the crate: C hands us raw pointers,
+//! and there's no way to check that they point where the caller says they do.
+//!
+//! Keys are passed as pointers to 10 bytes. `Key` is `#[repr(transparent)]` over
+//! `[u8; 10]`, so a pointer to a `Key` is exactly a pointer to its bytes.
+#![allow(unsafe_code)]
+
+use super::{encrypt_block, Key};
+
+/// Returned by the checked functions when they succeed.
+pub const SKIPJACK_OK: i32 = 0;
+
+/// Returned by the checked functions when a pointer argument is null.
+pub const SKIPJACK_ERR_NULL: i32 = -1;
+
+/// Encrypts a single 64-bit block with the 10-byte key at `key_ptr`.
+///
+/// If `key_ptr` is null, this returns 0 without encrypting anything. Since 0 is
+/// also a perfectly good ciphertext, callers that can't rule out a null key
+/// should use `skipjack_encrypt_block_checked` instead.
+///
+/// # Safety
+///
+/// `key_ptr` must either be null, or point to 10 readable bytes. This is the
+/// caller's responsibility: nothing here can check it.
+///
+/// # Arguments
+///
+/// * `block` - The block to encrypt
+/// * `key_ptr` - A pointer to the 10-byte secret key to encrypt with
+#[no_mangle]
+pub unsafe extern "C" fn skipjack_encrypt_block(block: u64, key_ptr: *const u8) -> u64 {
+    if key_ptr.is_null() {
+        return 0;
+    }
+
+    // `[u8; 10]` has an alignment of 1, so any non-null pointer is aligned.
+    let key = &*(key_ptr as *const [u8; 10]);
+
+    encrypt_block(block, key)
+}
+
+/// Encrypts a single 64-bit block, writing the ciphertext to `out`.
+///
+/// Returns `SKIPJACK_OK` on success, or `SKIPJACK_ERR_NULL` (leaving `out`
+/// untouched) if either pointer is null.
+///
+/// # Safety
+///
+/// `key` must either be null, or point to a valid `Key` (that is, 10 readable
+/// bytes). `out` must either be null, or point to a writable, aligned `u64`.
+///
+/// # Arguments
+///
+/// * `block` - The block to encrypt
+/// * `key` - A pointer to the secret key to encrypt with
+/// * `out` - Where to write the ciphertext
+#[no_mangle]
+pub unsafe extern "C" fn skipjack_encrypt_block_checked(
+    block: u64,
+    key: *const Key,
+    out: *mut u64,
+) -> i32 {
+    if key.is_null() || out.is_null() {
+        return SKIPJACK_ERR_NULL;
+    }
+
+    // Borrow the key, rather than copying it out of the caller's memory.
+    let key = &(*key).0;
+    *out = encrypt_block(block, key);
+
+    SKIPJACK_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_key_layout() {
+        assert_eq!(core::mem::size_of::<Key>(), 10);
+        assert_eq!(core::mem::align_of::<Key>(), 1);
+    }
+
+    #[test]
+    fn test_skipjack_encrypt_block() {
+        // Call through a function pointer with the C ABI, as C would.
+        let f: unsafe extern "C" fn(u64, *const u8) -> u64 = skipjack_encrypt_block;
+
+        let key = KEY;
+        assert_eq!(
+            unsafe { f(0x33221100ddccbbaa, key.as_ptr()) },
+            0x2587cae27a12d300
+        );
+        assert_eq!(unsafe { f(0x33221100ddccbbaa, ptr::null()) }, 0);
+    }
+
+    #[test]
+    fn test_skipjack_encrypt_block_checked() {
+        let key = Key(KEY);
+        let mut out = 0;
+
+        let status = unsafe { skipjack_encrypt_block_checked(0x33221100ddccbbaa, &key, &mut out) };
+        assert_eq!(status, SKIPJACK_OK);
+        assert_eq!(out, 0x2587cae27a12d300);
+
+        out = 0x1234;
+        let status =
+            unsafe { skipjack_encrypt_block_checked(0x33221100ddccbbaa, ptr::null(), &mut out) };
+        assert_eq!(status, SKIPJACK_ERR_NULL);
+        assert_eq!(out, 0x1234);
+
+        let status =
+            unsafe { skipjack_encrypt_block_checked(0x33221100ddccbbaa, &key, ptr::null_mut()) };
+        assert_eq!(status, SKIPJACK_ERR_NULL);
+    }
+}
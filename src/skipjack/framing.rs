@@ -0,0 +1,161 @@
+//! Framing with a CRC32, for formats that append one before encrypting.
+//!
+//! Some legacy formats append a CRC32 of the plaintext, pad, and encrypt the lot
+//! in CBC mode, so that the receiver can detect a corrupted message (or the wrong
+//! key). `seal_crc` and `open_crc` produce and consume that framing.
+//!
+//! **This is not authentication.** A CRC is a linear function with no key, so
+//! anyone who can modify ciphertext can also fix up the CRC to match. It only
+//! catches accidents. What's more, `open_crc` reports bad padding and a bad CRC
+//! as different errors, so a service that exposes them to an attacker is a
+//! padding oracle. If you need integrity against an adversary, use `aead`.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::convert::TryInto;
+
+use super::cbc::{cbc_step_decrypt, cbc_step_encrypt};
+use super::{pad_pkcs7, unpad_pkcs7, Error, BLOCK_SIZE};
+
+// The size of the trailing CRC, in bytes.
+const CRC_SIZE: usize = 4;
+
+// Computes the CRC32 (IEEE 802.3, as used by zip and PNG) of the given data.
+//
+// This is the straightforward bit-at-a-time version, with the reflected
+// polynomial: it's slower than a table-driven one, but there's nothing to get wrong.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            // XOR in the polynomial if the bit shifted out is set.
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb88320 & mask);
+        }
+    }
+
+    !crc
+}
+
+/// Appends a CRC32 of the given data, pads it (PKCS#7), and encrypts it in CBC mode.
+///
+/// The CRC is appended big-endian. See the module documentation: the CRC detects
+/// accidental corruption only, and is not a substitute for authentication.
+///
+/// # Arguments
+///
+/// * `data` - The data to frame and encrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt with
+pub fn seal_crc(data: &[u8], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(data.len() + CRC_SIZE);
+    framed.extend_from_slice(data);
+    framed.extend_from_slice(&crc32(data).to_be_bytes());
+
+    let mut ciphertext = pad_pkcs7(&framed);
+    let mut chain = iv.to_be_bytes();
+    for chunk in ciphertext.chunks_exact_mut(BLOCK_SIZE) {
+        cbc_step_encrypt(chunk.try_into().unwrap(), &mut chain, key.borrow());
+    }
+
+    ciphertext
+}
+
+/// Decrypts the output of `seal_crc`, removes the padding, and checks the CRC.
+///
+/// Returns `Error::BufferNotBlockAligned` if the data isn't a multiple of the
+/// block size, `Error::InvalidPadding` if the decrypted padding is malformed, or
+/// `Error::CrcMismatch` if the CRC doesn't match the data (or is missing).
+///
+/// # Arguments
+///
+/// * `data` - The data to decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to decrypt with
+pub fn open_crc(data: &[u8], iv: u64, key: impl Borrow<[u8; 10]>) -> Result<Vec<u8>, Error> {
+    if data.is_empty() || !data.len().is_multiple_of(BLOCK_SIZE) {
+        return Err(Error::BufferNotBlockAligned);
+    }
+
+    let mut plaintext = data.to_vec();
+    let mut chain = iv.to_be_bytes();
+    for chunk in plaintext.chunks_exact_mut(BLOCK_SIZE) {
+        cbc_step_decrypt(chunk.try_into().unwrap(), &mut chain, key.borrow());
+    }
+
+    let mut plaintext = unpad_pkcs7(plaintext)?;
+    if plaintext.len() < CRC_SIZE {
+        return Err(Error::CrcMismatch);
+    }
+
+    let (message, crc) = plaintext.split_at(plaintext.len() - CRC_SIZE);
+    if crc32(message).to_be_bytes() != crc {
+        return Err(Error::CrcMismatch);
+    }
+
+    plaintext.truncate(plaintext.len() - CRC_SIZE);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cbc::encrypt_cbc;
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_crc32() {
+        // The standard check value for CRC-32/ISO-HDLC.
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..=24 {
+            let data: Vec<u8> = (0..len as u8).collect();
+            let sealed = seal_crc(&data, IV, KEY);
+
+            // Data, then the CRC, then at least one byte of padding.
+            assert_eq!(
+                sealed.len(),
+                (len + CRC_SIZE) / BLOCK_SIZE * BLOCK_SIZE + BLOCK_SIZE
+            );
+            assert_eq!(open_crc(&sealed, IV, KEY), Ok(data));
+        }
+    }
+
+    #[test]
+    fn test_corruption() {
+        let data = b"attack at dawn, or maybe lunch";
+        let sealed = seal_crc(data, IV, KEY);
+
+        // Flipping a bit in the first ciphertext block garbles that block's
+        // plaintext, which the CRC catches; the padding, at the end, is intact.
+        let mut corrupted = sealed.clone();
+        corrupted[0] ^= 0x01;
+        assert_eq!(open_crc(&corrupted, IV, KEY), Err(Error::CrcMismatch));
+
+        // So does the wrong IV, which flips a bit of the first plaintext block.
+        assert_eq!(open_crc(&sealed, IV ^ 1, KEY), Err(Error::CrcMismatch));
+
+        assert_eq!(
+            open_crc(&sealed[1..], IV, KEY),
+            Err(Error::BufferNotBlockAligned)
+        );
+        assert_eq!(open_crc(&[], IV, KEY), Err(Error::BufferNotBlockAligned));
+    }
+
+    #[test]
+    fn test_missing_crc() {
+        // Validly padded, but too short to hold a CRC.
+        let blocks = encrypt_cbc(&[0x4142430505050505], IV, KEY);
+        let sealed: Vec<u8> = blocks.iter().flat_map(|b| b.to_be_bytes()).collect();
+
+        assert_eq!(open_crc(&sealed, IV, KEY), Err(Error::CrcMismatch));
+    }
+}
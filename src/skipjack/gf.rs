@@ -0,0 +1,83 @@
+//! Arithmetic in GF(2^64), as needed by CMAC and `mac::wc_mac`.
+//!
+//! Field elements are represented as `u64`s, with the most significant bit
+//! holding the coefficient of x^63. Products are reduced by the polynomial
+//! x^64 + x^4 + x^3 + x + 1, which is the one NIST SP 800-38B specifies for
+//! 64-bit block ciphers.
+
+// The low terms of the reduction polynomial, i.e. x^4 + x^3 + x + 1.
+const R: u64 = 0x1b;
+
+/// Multiplies the given field element by x (i.e., "doubles" it).
+///
+/// # Arguments
+///
+/// * `x` - The field element to double
+pub fn dbl(x: u64) -> u64 {
+    // Shifting left multiplies by x. If that shifts out an x^64 term, we reduce
+    // by XOR'ing in the rest of the polynomial. Rather than branching on the
+    // high bit, we turn it into a mask (all ones or all zeros) and apply it.
+    let carry = x >> 63;
+
+    (x << 1) ^ (carry.wrapping_neg() & R)
+}
+
+/// Multiplies two field elements.
+///
+/// This is schoolbook multiplication: for each bit of `b`, from x^0 up, add
+/// (XOR) in `a` times that power of x, doubling `a` as we go. Like `dbl`, it
+/// uses masks rather than branches, so its running time doesn't depend on the
+/// operands. It's slow next to a carry-less multiply instruction, but portable.
+///
+/// # Arguments
+///
+/// * `a` - The first field element
+/// * `b` - The second field element
+pub fn mul(a: u64, b: u64) -> u64 {
+    let mut a = a;
+    let mut product = 0;
+
+    for i in 0..64 {
+        let bit = (b >> i) & 1;
+        product ^= a & bit.wrapping_neg();
+        a = dbl(a);
+    }
+
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dbl() {
+        assert_eq!(dbl(0), 0);
+        assert_eq!(dbl(1), 2);
+        assert_eq!(dbl(0x4000000000000000), 0x8000000000000000);
+
+        // x^63 * x = x^64, which reduces to x^4 + x^3 + x + 1.
+        assert_eq!(dbl(0x8000000000000000), R);
+        assert_eq!(dbl(0xffffffffffffffff), 0xffffffffffffffe5);
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = 0x0123456789abcdef;
+        let b = 0xfedcba9876543210;
+
+        // Checked against a carry-less multiply and reduction in Python.
+        assert_eq!(mul(a, b), 0x48827ab55d976fa0);
+
+        assert_eq!(mul(a, 0), 0);
+        assert_eq!(mul(a, 1), a);
+        assert_eq!(mul(a, 2), dbl(a));
+        assert_eq!(mul(0x8000000000000000, 2), R);
+
+        // The field axioms, for a few elements.
+        let c = 0x9e3779b97f4a7c15;
+        assert_eq!(mul(a, b), mul(b, a));
+        assert_eq!(mul(mul(a, b), c), mul(a, mul(b, c)));
+        assert_eq!(mul(a, b ^ c), mul(a, b) ^ mul(a, c));
+    }
+}
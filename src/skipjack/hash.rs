@@ -0,0 +1,190 @@
+//! A `Hasher` built on Skipjack, for demonstration purposes only.
+//!
+//! **This is neither a secure nor a fast hash.** It exists to show the block
+//! function in an unusual setting: input bytes are absorbed eight at a time by
+//! CBC-MAC-style chaining, i.e. each full block is XOR'ed into the state and the
+//! state is then encrypted. Use `std::collections::hash_map::RandomState` (or
+//! anything else) for real work.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{BuildHasher, Hasher};
+
+use super::{Cipher, BLOCK_SIZE};
+
+// The key used by `SkipjackBuildHasher::default()`. This is the key from the
+// NIST specification's worked example, and has no special properties.
+const DEFAULT_KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+/// A `Hasher` that chains input blocks through Skipjack.
+#[derive(Clone)]
+pub struct SkipjackHasher {
+    cipher: Cipher,
+    state: u64,
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+    length: u64,
+}
+
+impl SkipjackHasher {
+    /// Creates a new hasher with the given 80-bit key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to chain input blocks with
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        SkipjackHasher::with_cipher(Cipher::new(key))
+    }
+
+    // Creates a new hasher that chains with the given cipher, so that a
+    // `SkipjackBuildHasher` can hand out hashers without re-running the key
+    // schedule for each one.
+    fn with_cipher(cipher: Cipher) -> Self {
+        SkipjackHasher {
+            cipher,
+            state: 0,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+            length: 0,
+        }
+    }
+}
+
+impl Hasher for SkipjackHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.buffer[self.buffered] = *byte;
+            self.buffered += 1;
+
+            // Once we have a full block, chain it into the state.
+            if self.buffered == BLOCK_SIZE {
+                let block = u64::from_be_bytes(self.buffer);
+                self.state = self.cipher.encrypt_block(self.state ^ block);
+                self.buffered = 0;
+            }
+        }
+
+        self.length = self.length.wrapping_add(bytes.len() as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        // `finish` doesn't consume the hasher, so we finalize a copy of the state.
+        // Any partial block is zero-filled and chained in, followed by the total
+        // length, so that inputs differing only by trailing zeros don't collide.
+        let mut state = self.state;
+
+        if self.buffered > 0 {
+            let mut block = [0u8; BLOCK_SIZE];
+            block[..self.buffered].copy_from_slice(&self.buffer[..self.buffered]);
+            state = self.cipher.encrypt_block(state ^ u64::from_be_bytes(block));
+        }
+
+        self.cipher.encrypt_block(state ^ self.length)
+    }
+}
+
+impl fmt::Debug for SkipjackHasher {
+    // Never include the key (or anything derived from it) in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkipjackHasher").finish_non_exhaustive()
+    }
+}
+
+/// A `BuildHasher` producing `SkipjackHasher`s, for use with `HashMap` and friends.
+#[derive(Clone)]
+pub struct SkipjackBuildHasher {
+    cipher: Cipher,
+}
+
+impl SkipjackBuildHasher {
+    /// Creates a new `BuildHasher` whose hashers are seeded with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key each hasher is created with
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        SkipjackBuildHasher {
+            cipher: Cipher::new(key),
+        }
+    }
+}
+
+impl Default for SkipjackBuildHasher {
+    fn default() -> Self {
+        SkipjackBuildHasher::new(DEFAULT_KEY)
+    }
+}
+
+impl BuildHasher for SkipjackBuildHasher {
+    type Hasher = SkipjackHasher;
+
+    fn build_hasher(&self) -> SkipjackHasher {
+        SkipjackHasher::with_cipher(self.cipher.clone())
+    }
+}
+
+impl fmt::Debug for SkipjackBuildHasher {
+    // Never include the key (or anything derived from it) in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkipjackBuildHasher")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hashmap() {
+        let mut map: HashMap<&str, usize, SkipjackBuildHasher> = HashMap::default();
+
+        let words = ["skipjack", "clipper", "capstone", "fortezza", ""];
+        for (i, word) in words.iter().enumerate() {
+            map.insert(word, i);
+        }
+
+        assert_eq!(map.len(), words.len());
+        for (i, word) in words.iter().enumerate() {
+            assert_eq!(map.get(word), Some(&i));
+        }
+        assert_eq!(map.get("kea"), None);
+    }
+
+    #[test]
+    fn test_hasher_consistency() {
+        // Equal inputs hash equally, regardless of how they're split across writes.
+        let mut one = SkipjackHasher::new(DEFAULT_KEY);
+        one.write(b"the quick brown fox");
+
+        let mut two = SkipjackHasher::new(DEFAULT_KEY);
+        two.write(b"the quick");
+        two.write(b" brown fox");
+
+        assert_eq!(one.finish(), two.finish());
+
+        // Trailing zeros are not absorbed by the padding.
+        let mut three = SkipjackHasher::new(DEFAULT_KEY);
+        three.write(b"the quick brown fox\0");
+
+        assert_ne!(one.finish(), three.finish());
+
+        // A hasher from a `SkipjackBuildHasher` agrees with one made directly.
+        let mut four = SkipjackBuildHasher::new(DEFAULT_KEY).build_hasher();
+        four.write(b"the quick brown fox");
+
+        assert_eq!(one.finish(), four.finish());
+    }
+
+    #[test]
+    fn test_debug_omits_key() {
+        let build = SkipjackBuildHasher::new(DEFAULT_KEY);
+
+        assert_eq!(format!("{:?}", build), "SkipjackBuildHasher { .. }");
+        assert_eq!(
+            format!("{:?}", build.build_hasher()),
+            "SkipjackHasher { .. }"
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Adapters for `std::io` streams.
+//!
+//! `EcbEncryptor` and `EcbDecryptor` wrap a reader, and encrypt or decrypt its
+//! contents as they're read, with the same PKCS#7 padding as `encrypt_padded`
+//! and `decrypt_padded`. Only a block or two is buffered at a time, so they can
+//! process streams of any size.
+//!
+//! `BlockReader` and `BlockWriter` read and write streams of raw 64-bit
+//! blocks, in either byte order, for use with the block-level functions.
+
+use core::borrow::Borrow;
+use std::io::{self, Read, Write};
+
+use super::{pkcs7_padding_len, Cipher, Error, BLOCK_SIZE};
+
+// Converts one of our errors into an I/O error, for the `Read` implementations.
+fn invalid_data(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+// Reads from `reader` until `block` is full or the reader is exhausted,
+// returning the number of bytes read. A return value less than `BLOCK_SIZE`
+// means EOF.
+fn fill_block<R: Read>(reader: &mut R, block: &mut [u8; BLOCK_SIZE]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < BLOCK_SIZE {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filled)
+}
+
+// A block of output waiting to be read, and how much of it has been.
+struct Output {
+    block: [u8; BLOCK_SIZE],
+    start: usize,
+    end: usize,
+}
+
+impl Output {
+    fn new() -> Self {
+        Output {
+            block: [0; BLOCK_SIZE],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    fn set(&mut self, block: [u8; BLOCK_SIZE], len: usize) {
+        self.block = block;
+        self.start = 0;
+        self.end = len;
+    }
+
+    // Copies as much pending output as will fit into `buf`.
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let n = buf.len().min(self.end - self.start);
+        buf[..n].copy_from_slice(&self.block[self.start..self.start + n]);
+        self.start += n;
+
+        n
+    }
+}
+
+/// A reader that encrypts everything read from another reader (ECB, with
+/// PKCS#7 padding).
+///
+/// The output is identical to `encrypt_padded` on the reader's entire contents:
+/// blocks are encrypted as soon as they're complete, and the final (partial or
+/// empty) block is padded once the inner reader reaches EOF.
+pub struct EcbEncryptor<R: Read> {
+    inner: R,
+    cipher: Cipher,
+    output: Output,
+    finished: bool,
+}
+
+impl<R: Read> EcbEncryptor<R> {
+    /// Creates a new encrypting reader.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader to read plaintext from
+    /// * `key` - The secret key to encrypt with
+    pub fn new(inner: R, key: impl Borrow<[u8; 10]>) -> Self {
+        EcbEncryptor {
+            inner,
+            cipher: Cipher::new(key),
+            output: Output::new(),
+            finished: false,
+        }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for EcbEncryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.output.is_empty() && !self.finished && !buf.is_empty() {
+            let mut block = [0u8; BLOCK_SIZE];
+            let filled = fill_block(&mut self.inner, &mut block)?;
+
+            // A short block means EOF, so pad it. (PKCS#7 always pads, so an
+            // input that ends on a block boundary gets a whole block of padding
+            // on the next call.)
+            if filled < BLOCK_SIZE {
+                let pad = BLOCK_SIZE - filled;
+                for byte in block[filled..].iter_mut() {
+                    *byte = pad as u8;
+                }
+                self.finished = true;
+            }
+
+            let ciphertext = self.cipher.encrypt_block(block).to_be_bytes();
+            self.output.set(ciphertext, BLOCK_SIZE);
+        }
+
+        Ok(self.output.read(buf))
+    }
+}
+
+/// A reader that decrypts everything read from another reader (ECB, with
+/// PKCS#7 padding), the inverse of `EcbEncryptor`.
+///
+/// Padding can only be removed from the final block, and there's no way to
+/// tell that a block is final until the next read hits EOF. So each decrypted
+/// block is held back until the one after it has been read.
+///
+/// Malformed input is reported as an `io::ErrorKind::InvalidData` error that
+/// wraps `Error::BufferNotBlockAligned` (if the input isn't a whole number of
+/// blocks) or `Error::InvalidPadding`.
+pub struct EcbDecryptor<R: Read> {
+    inner: R,
+    cipher: Cipher,
+    // The most recently decrypted block, which may turn out to be the last.
+    pending: Option<[u8; BLOCK_SIZE]>,
+    output: Output,
+    finished: bool,
+}
+
+impl<R: Read> EcbDecryptor<R> {
+    /// Creates a new decrypting reader.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader to read ciphertext from
+    /// * `key` - The secret key to decrypt with
+    pub fn new(inner: R, key: impl Borrow<[u8; 10]>) -> Self {
+        EcbDecryptor {
+            inner,
+            cipher: Cipher::new(key),
+            pending: None,
+            output: Output::new(),
+            finished: false,
+        }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for EcbDecryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output.is_empty() && !self.finished && !buf.is_empty() {
+            let mut block = [0u8; BLOCK_SIZE];
+            let filled = fill_block(&mut self.inner, &mut block)?;
+
+            match (filled, self.pending) {
+                // Another full block: the pending block wasn't the last, so it
+                // can be released as-is.
+                (BLOCK_SIZE, pending) => {
+                    let plaintext = self.cipher.decrypt_block(block).to_be_bytes();
+                    if let Some(previous) = pending {
+                        self.output.set(previous, BLOCK_SIZE);
+                    }
+                    self.pending = Some(plaintext);
+                }
+                // EOF on a block boundary: the pending block is the last, and
+                // carries the padding.
+                (0, Some(last)) => {
+                    let pad = pkcs7_padding_len(&last).map_err(invalid_data)?;
+                    self.output.set(last, BLOCK_SIZE - pad);
+                    self.pending = None;
+                    self.finished = true;
+                }
+                // EOF anywhere else: either the input was empty (and so has no
+                // padding), or it ended partway through a block.
+                _ => return Err(invalid_data(Error::BufferNotBlockAligned)),
+            }
+        }
+
+        Ok(self.output.read(buf))
+    }
+}
+
+/// The byte order of blocks in a stream.
+///
+/// This crate treats blocks as big-endian everywhere else (the first byte of a
+/// block is its most significant), but some tools write them little-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Most significant byte first, as in the rest of this crate.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+/// An iterator over the 64-bit blocks in a reader.
+///
+/// Each item is the next 8 bytes of the reader, assembled into a block in the
+/// given byte order. Iteration stops at EOF; if the reader ends partway through
+/// a block, the last item is an `io::ErrorKind::UnexpectedEof` error.
+pub struct BlockReader<R: Read> {
+    inner: R,
+    endianness: Endianness,
+}
+
+impl<R: Read> BlockReader<R> {
+    /// Creates a new block reader.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The reader to read blocks from
+    /// * `endianness` - The byte order of the blocks
+    pub fn new(inner: R, endianness: Endianness) -> Self {
+        BlockReader { inner, endianness }
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Iterator for BlockReader<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<io::Result<u64>> {
+        let mut bytes = [0u8; BLOCK_SIZE];
+
+        match fill_block(&mut self.inner, &mut bytes) {
+            Ok(0) => None,
+            Ok(BLOCK_SIZE) => Some(Ok(match self.endianness {
+                Endianness::Big => u64::from_be_bytes(bytes),
+                Endianness::Little => u64::from_le_bytes(bytes),
+            })),
+            Ok(_) => Some(Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ended partway through a block",
+            ))),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// A writer of 64-bit blocks, the counterpart to `BlockReader`.
+pub struct BlockWriter<W: Write> {
+    inner: W,
+    endianness: Endianness,
+}
+
+impl<W: Write> BlockWriter<W> {
+    /// Creates a new block writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The writer to write blocks to
+    /// * `endianness` - The byte order to write blocks in
+    pub fn new(inner: W, endianness: Endianness) -> Self {
+        BlockWriter { inner, endianness }
+    }
+
+    /// Writes a single block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The block to write
+    pub fn write_block(&mut self, block: u64) -> io::Result<()> {
+        let bytes = match self.endianness {
+            Endianness::Big => block.to_be_bytes(),
+            Endianness::Little => block.to_le_bytes(),
+        };
+
+        self.inner.write_all(&bytes)
+    }
+
+    /// Flushes the inner writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::super::{decrypt_padded, encrypt_padded};
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    // A reader that returns at most one byte per call, to exercise buffering
+    // across calls.
+    struct Trickle<R: Read>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    // Reads everything from `reader`, a few bytes at a time.
+    fn read_in_pieces(mut reader: impl Read) -> io::Result<Vec<u8>> {
+        let mut result = Vec::new();
+        let mut buf = [0u8; 3];
+        loop {
+            match reader.read(&mut buf)? {
+                0 => return Ok(result),
+                n => result.extend_from_slice(&buf[..n]),
+            }
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..40 {
+            let plaintext: Vec<u8> = (0..len as u8).collect();
+
+            let mut ciphertext = Vec::new();
+            EcbEncryptor::new(Cursor::new(&plaintext), KEY)
+                .read_to_end(&mut ciphertext)
+                .unwrap();
+            assert_eq!(ciphertext, encrypt_padded(&plaintext, KEY));
+
+            let mut decrypted = Vec::new();
+            EcbDecryptor::new(Cursor::new(&ciphertext), KEY)
+                .read_to_end(&mut decrypted)
+                .unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn test_partial_reads() {
+        let plaintext = b"split across many small reads, in both directions";
+
+        let encryptor = EcbEncryptor::new(Trickle(Cursor::new(&plaintext[..])), KEY);
+        let ciphertext = read_in_pieces(encryptor).unwrap();
+        assert_eq!(ciphertext, encrypt_padded(plaintext, KEY));
+
+        let decryptor = EcbDecryptor::new(Trickle(Cursor::new(&ciphertext)), KEY);
+        assert_eq!(read_in_pieces(decryptor).unwrap(), &plaintext[..]);
+    }
+
+    #[test]
+    fn test_block_reader_writer() {
+        let blocks = [
+            0x33221100ddccbbaa,
+            0,
+            0xffffffffffffffff,
+            0x0123456789abcdef,
+        ];
+
+        for &endianness in [Endianness::Big, Endianness::Little].iter() {
+            let mut writer = BlockWriter::new(Vec::new(), endianness);
+            for &block in blocks.iter() {
+                writer.write_block(block).unwrap();
+            }
+            let bytes = writer.into_inner();
+            assert_eq!(bytes.len(), blocks.len() * BLOCK_SIZE);
+
+            let reader = BlockReader::new(Trickle(Cursor::new(&bytes)), endianness);
+            let read: Vec<u64> = reader.collect::<io::Result<_>>().unwrap();
+            assert_eq!(read, blocks);
+        }
+
+        // The byte order is the only difference between the two.
+        let mut writer = BlockWriter::new(Vec::new(), Endianness::Little);
+        writer.write_block(0x33221100ddccbbaa).unwrap();
+        assert_eq!(
+            writer.into_inner(),
+            [0xaa, 0xbb, 0xcc, 0xdd, 0x00, 0x11, 0x22, 0x33]
+        );
+    }
+
+    #[test]
+    fn test_block_reader_truncated() {
+        let bytes = [0u8; 12];
+        let mut reader = BlockReader::new(Cursor::new(&bytes), Endianness::Big);
+
+        assert_eq!(reader.next().unwrap().unwrap(), 0);
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let ciphertext = encrypt_padded(b"some data", KEY);
+
+        // Truncated mid-block, and empty.
+        for input in [&ciphertext[..ciphertext.len() - 1], &[][..]].iter() {
+            let err = EcbDecryptor::new(Cursor::new(input), KEY)
+                .read_to_end(&mut Vec::new())
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), Error::BufferNotBlockAligned.to_string());
+        }
+
+        // The wrong key, which garbles the padding.
+        let wrong_key = [0xff; 10];
+        assert_eq!(
+            decrypt_padded(&ciphertext, wrong_key),
+            Err(Error::InvalidPadding)
+        );
+        let err = EcbDecryptor::new(Cursor::new(&ciphertext), wrong_key)
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), Error::InvalidPadding.to_string());
+    }
+}
@@ -0,0 +1,88 @@
+//! Deriving secret keys from passphrases.
+//!
+//! A passphrase isn't a key: it's usually short, guessable, and not 10 bytes
+//! long. `key_from_passphrase` stretches one into a key with PBKDF2-HMAC-SHA256
+//! (RFC 8018), which makes each guess at the passphrase expensive.
+//!
+//! None of that can make the result stronger than Skipjack itself. However good
+//! the passphrase, the key is 80 bits, and a well-funded attacker can search 2^80
+//! keys directly, without touching the KDF.
+
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+
+/// The number of PBKDF2 iterations used by `key_from_passphrase`.
+///
+/// This is 600,000: the OWASP recommendation for PBKDF2-HMAC-SHA256 (as of 2023).
+/// It's fixed, so that the same passphrase and salt always give the same key.
+pub const ITERATIONS: u32 = 600_000;
+
+// Derives a key with the given number of iterations. PBKDF2 produces as many
+// bytes as asked for, so the output is exactly one key's worth.
+fn derive(passphrase: &[u8], salt: &[u8], iterations: u32) -> [u8; 10] {
+    let mut key = [0u8; 10];
+    pbkdf2_hmac::<Sha256>(passphrase, salt, iterations, &mut key);
+
+    key
+}
+
+/// Derives an 80-bit secret key from a passphrase, with PBKDF2-HMAC-SHA256.
+///
+/// The same passphrase and salt always give the same key. The salt should be
+/// random and unique for each passphrase (16 bytes is plenty), and stored
+/// alongside whatever the key protects: it isn't secret, but without it the
+/// key can't be derived again.
+///
+/// This is deliberately slow (see `ITERATIONS`), and remember that the key it
+/// returns is still only 80 bits: see the module documentation.
+///
+/// # Arguments
+///
+/// * `passphrase` - The passphrase to derive the key from
+/// * `salt` - The salt to derive the key with
+pub fn key_from_passphrase(passphrase: &str, salt: &[u8]) -> [u8; 10] {
+    derive(passphrase.as_bytes(), salt, ITERATIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive() {
+        // Checked against Python's `hashlib.pbkdf2_hmac`.
+        assert_eq!(
+            derive(b"password", b"salt", 1),
+            [0x12, 0x0f, 0xb6, 0xcf, 0xfc, 0xf8, 0xb3, 0x2c, 0x43, 0xe7]
+        );
+        assert_eq!(
+            derive(b"password", b"salt", 2),
+            [0xae, 0x4d, 0x0c, 0x95, 0xaf, 0x6b, 0x46, 0xd3, 0x2d, 0x0a]
+        );
+
+        // Deterministic, but sensitive to the salt and the passphrase. (Fewer
+        // iterations than the real thing, so that this doesn't take all day.)
+        let key = derive(b"correct horse battery staple", b"skipjack.rs", 1000);
+        assert_eq!(
+            derive(b"correct horse battery staple", b"skipjack.rs", 1000),
+            key
+        );
+        assert_ne!(
+            derive(b"correct horse battery staple", b"skipjack.rt", 1000),
+            key
+        );
+        assert_ne!(
+            derive(b"correct horse battery stapl", b"skipjack.rs", 1000),
+            key
+        );
+    }
+
+    #[test]
+    fn test_key_from_passphrase() {
+        // Also checked against `hashlib.pbkdf2_hmac`, with 600,000 iterations.
+        assert_eq!(
+            key_from_passphrase("correct horse battery staple", b"skipjack.rs"),
+            [0x61, 0x18, 0x18, 0x03, 0x82, 0xea, 0x06, 0xae, 0xcc, 0x4e]
+        );
+    }
+}
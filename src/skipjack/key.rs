@@ -0,0 +1,473 @@
+//! Skipjack's 80-bit secret key, and conversions to and from other representations.
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use super::util::{hex_bytes_fixed, hex_decode_fixed};
+use super::{encrypt_block, Error, IntoBlock};
+
+// The bits of a `u128` that a key may occupy: the low 80.
+const KEY_MASK: u128 = (1 << 80) - 1;
+
+/// Converts the low 80 bits of a `u128` into a secret key.
+///
+/// The key is big-endian: `key[0]` holds the most significant byte of the 80-bit
+/// value, and `key[9]` the least significant. If any of the top 48 bits are
+/// set, the value doesn't fit in a key and `Error::KeyOutOfRange` is returned.
+///
+/// # Arguments
+///
+/// * `v` - The key, as an integer less than 2^80
+pub fn key_from_u128(v: u128) -> Result<[u8; 10], Error> {
+    if v & !KEY_MASK != 0 {
+        return Err(Error::KeyOutOfRange);
+    }
+
+    // The key is the last 10 of the 16 big-endian bytes; the first 6 are zero.
+    let mut key = [0u8; 10];
+    key.copy_from_slice(&v.to_be_bytes()[6..]);
+
+    Ok(key)
+}
+
+/// Converts a secret key into a `u128`, with the key in the low 80 bits.
+///
+/// This is the inverse of `key_from_u128`: `key[0]` becomes the most significant
+/// byte of the 80-bit value.
+///
+/// # Arguments
+///
+/// * `key` - The secret key to convert
+pub fn key_to_u128(key: &[u8; 10]) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes[6..].copy_from_slice(key);
+
+    u128::from_be_bytes(bytes)
+}
+
+// Encrypts the given block under the key XOR'ed with the given mask.
+// Constructions that need a value derived from the key, which nothing else
+// computes, encrypt under a key masked with a constant of their own.
+pub(crate) fn encrypt_block_masked(block: u64, key: &[u8; 10], mask: &[u8; 10]) -> u64 {
+    let mut masked = *key;
+    for (k, m) in masked.iter_mut().zip(mask.iter()) {
+        *k ^= m;
+    }
+
+    // Borrow the masked key, so that the copy we wipe is the only one. (The
+    // allow is only needed because `encrypt_block` takes `impl Borrow`, and
+    // without `zeroize`, clippy sees no later use of the key.)
+    #[allow(clippy::needless_borrows_for_generic_args)]
+    let ciphertext = encrypt_block(block, &masked);
+
+    #[cfg(feature = "zeroize")]
+    masked.zeroize();
+
+    ciphertext
+}
+
+// `key_fingerprint` encrypts this block under the key XOR'ed with this mask.
+// Nothing else in the crate uses this mask, so the fingerprint is never an
+// input to (or an output of) any mode or MAC.
+const FINGERPRINT_TAG: u64 = u64::from_be_bytes(*b"keyprint");
+const FINGERPRINT_MASK: [u8; 10] = *b"fingerprnt";
+
+/// Returns a short, non-secret fingerprint of the given key.
+///
+/// The fingerprint is a fixed tag block, encrypted under the key XOR'ed with a
+/// fixed mask. It's meant for operational checks like "did both ends load the
+/// same key?", e.g. by writing it to a log, without writing the key itself.
+///
+/// Because of the mask, the fingerprint is never a block encrypted under the
+/// key itself: it isn't a CTR keystream block, CMAC's `L`, `wc_mac`'s hash key,
+/// or anything else that the modes and MACs here compute, so logging it gives
+/// none of those away. (The obvious fingerprint, the encryption of the
+/// all-zero block, would give away several of them at once.)
+///
+/// It is still **not** a security mechanism. It's a known plaintext and
+/// ciphertext under a key that differs from yours by a public mask, so
+/// recovering the key from it is as hard as a known-plaintext attack on
+/// Skipjack, which with an 80-bit key is hard but not out of reach. And it
+/// isn't collision resistant: different keys can share a fingerprint, though
+/// two random keys will only do so with probability 2^-64.
+///
+/// # Arguments
+///
+/// * `key` - The secret key to fingerprint
+pub fn key_fingerprint(key: &[u8; 10]) -> u64 {
+    encrypt_block_masked(FINGERPRINT_TAG, key, &FINGERPRINT_MASK)
+}
+
+/// Encrypts the given 64-bit block with a key that's been split into shares.
+///
+/// The key is the XOR of all of the shares, so that no one share (or any
+/// set of them short of all) says anything about it: split a key `k` into
+/// random `a` and `k ^ a`, and neither half alone is any use. The key is put
+/// back together in a local variable only for as long as it takes to encrypt
+/// the block, and with the `zeroize` feature, that variable is wiped
+/// afterwards. (As with all zeroization here, this is best-effort: copies may
+/// survive in registers or on the stack.)
+///
+/// This is for demonstrating split key custody. It doesn't make the key any
+/// stronger, and the shares need the same care as the key itself.
+///
+/// Returns `Error::NoKeyShares` if `shares` is empty, since there's no key to
+/// reconstruct.
+///
+/// # Arguments
+///
+/// * `block` - The block to encrypt, as a `u64`, `[u8; 8]`, or `Block`
+/// * `shares` - The shares of the secret key to encrypt with
+pub fn encrypt_block_shared<B: IntoBlock>(block: B, shares: &[[u8; 10]]) -> Result<u64, Error> {
+    if shares.is_empty() {
+        return Err(Error::NoKeyShares);
+    }
+
+    let mut key = [0u8; 10];
+    for share in shares {
+        for (k, s) in key.iter_mut().zip(share.iter()) {
+            *k ^= s;
+        }
+    }
+
+    // Borrow the key, so that the copy we wipe is the only one. (As in
+    // `encrypt_block_masked`, the allow is only needed because `encrypt_block`
+    // takes `impl Borrow`, and without `zeroize`, clippy sees no later use of
+    // the key.)
+    #[allow(clippy::needless_borrows_for_generic_args)]
+    let ciphertext = encrypt_block(block, &key);
+
+    #[cfg(feature = "zeroize")]
+    key.zeroize();
+
+    Ok(ciphertext)
+}
+
+/// Returns whether the given key is degenerate, and should be avoided.
+///
+/// To be clear about the state of knowledge: no class of weak keys for full
+/// 32-round Skipjack has been published, in the sense that DES has weak keys.
+/// The declassified design review (Brickell et al., "SKIPJACK Review: Interim
+/// Report", 1993) didn't report any, and the best published attacks
+/// (e.g. Biham, Biryukov and Shamir's impossible differentials against 31
+/// rounds, EUROCRYPT 1999; Knudsen, Robshaw and Wagner's truncated
+/// differentials, CRYPTO 1999) work for all keys alike.
+///
+/// What this flags instead are keys that make the key schedule collapse.
+/// Step `k` uses key bytes `4k` through `4k + 3` (mod 10), so if the key
+/// repeats with period 2 (`abababababab...`, which includes keys whose bytes
+/// are all equal), every one of the 32 rounds uses the *same* four key bytes.
+/// Only the round counter then distinguishes one round from the next. That
+/// isn't a known break (the counter is there precisely to stop rounds with
+/// identical keys from being interchangeable), but it's a structure no
+/// well-chosen key has, and only 2^16 of the 2^80 keys have it: a key like
+/// this almost certainly came from a bug, a placeholder, or a bad generator,
+/// not from a random source.
+///
+/// No other period collapses the schedule: the schedule advances 4 bytes per
+/// step, and only periods 1 and 2 divide that.
+///
+/// # Arguments
+///
+/// * `key` - The secret key to check
+pub fn is_weak_key(key: &[u8; 10]) -> bool {
+    key.iter().skip(2).zip(key.iter()).all(|(a, b)| a == b)
+}
+
+/// An 80-bit Skipjack secret key.
+///
+/// This is a thin wrapper around `[u8; 10]`, mostly useful for parsing keys
+/// from text (see its `FromStr` implementation). Like `Cipher`, its `Debug`
+/// output never includes the key itself.
+///
+/// It's `#[repr(transparent)]`, so it has exactly the layout of `[u8; 10]`: a
+/// pointer to a `Key` can be passed to (or from) C as a pointer to 10 bytes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Key(pub [u8; 10]);
+
+impl From<[u8; 10]> for Key {
+    fn from(key: [u8; 10]) -> Self {
+        Key(key)
+    }
+}
+
+impl From<Key> for [u8; 10] {
+    fn from(key: Key) -> Self {
+        key.0
+    }
+}
+
+impl TryFrom<u128> for Key {
+    type Error = Error;
+
+    /// Converts the low 80 bits of a `u128` into a key, big-endian, like
+    /// `key_from_u128`.
+    ///
+    /// Returns `Error::KeyOutOfRange` if any of the top 48 bits are set.
+    fn try_from(v: u128) -> Result<Self, Self::Error> {
+        key_from_u128(v).map(Key)
+    }
+}
+
+impl AsRef<[u8]> for Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+// Parses each token as a hex byte, and checks that there are exactly 10 of them.
+fn key_from_hex_bytes<'a>(tokens: impl Iterator<Item = &'a [u8]>) -> Result<Key, Error> {
+    match hex_bytes_fixed(tokens) {
+        Ok(key) => Ok(Key(key)),
+        Err(Error::InvalidHexLength) => Err(Error::InvalidKeyLength),
+        Err(e) => Err(e),
+    }
+}
+
+impl FromStr for Key {
+    type Err = Error;
+
+    /// Parses a key written as hex bytes, e.g. as found in a config file.
+    ///
+    /// Three forms are accepted: contiguous (`00998877665544332211`), colon-separated
+    /// (`00:99:88:77:66:55:44:33:22:11`), and whitespace-separated
+    /// (`00 99 88 77 66 55 44 33 22 11`). When separated, each byte must be
+    /// exactly two digits, and the two kinds of separator can't be mixed. Hex digits
+    /// may be in either case, and surrounding whitespace is ignored.
+    ///
+    /// Returns `Error::InvalidHex` if any byte isn't two hex digits, or
+    /// `Error::InvalidKeyLength` if there aren't exactly 10 bytes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.contains(':') {
+            key_from_hex_bytes(s.split(':').map(str::as_bytes))
+        } else if s.contains(char::is_whitespace) {
+            key_from_hex_bytes(s.split_whitespace().map(str::as_bytes))
+        } else {
+            match hex_decode_fixed(s) {
+                Ok(key) => Ok(Key(key)),
+                Err(Error::InvalidHexLength) => Err(Error::InvalidKeyLength),
+                Err(e) => Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_u128() {
+        let key = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+        assert_eq!(key_to_u128(&key), 0x00998877665544332211);
+        assert_eq!(key_from_u128(0x00998877665544332211), Ok(key));
+
+        let keys = [
+            [0; 10],
+            [0xff; 10],
+            [0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01],
+            *b"0123456789",
+        ];
+        for key in keys.iter() {
+            assert_eq!(key_from_u128(key_to_u128(key)), Ok(*key));
+        }
+
+        assert_eq!(key_to_u128(&[0xff; 10]), KEY_MASK);
+        assert_eq!(
+            key_from_u128(1 << 79),
+            Ok([0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_key_from_u128_out_of_range() {
+        assert_eq!(key_from_u128(1 << 80), Err(Error::KeyOutOfRange));
+        assert_eq!(key_from_u128(u128::MAX), Err(Error::KeyOutOfRange));
+        assert_eq!(key_from_u128(1 << 127 | 1), Err(Error::KeyOutOfRange));
+    }
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_key_try_from_u128() {
+        let key = Key::try_from(0x00998877665544332211u128).unwrap();
+        assert_eq!(
+            key.0,
+            [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11]
+        );
+
+        // The same byte order as `key_to_u128`, at both ends of the range.
+        for &v in [0, 1, 0x0123456789abcdef0123, (1 << 80) - 1].iter() {
+            assert_eq!(key_to_u128(&Key::try_from(v).unwrap().0), v);
+        }
+        assert_eq!(Key::try_from(1u128).unwrap().0[9], 1);
+        assert_eq!(Key::try_from(1u128 << 79).unwrap().0[0], 0x80);
+
+        assert_eq!(Key::try_from(1u128 << 80), Err(Error::KeyOutOfRange));
+        assert_eq!(Key::try_from(u128::MAX), Err(Error::KeyOutOfRange));
+    }
+
+    #[test]
+    fn test_key_from_str() {
+        assert_eq!("00998877665544332211".parse(), Ok(Key(KEY)));
+        assert_eq!("00:99:88:77:66:55:44:33:22:11".parse(), Ok(Key(KEY)));
+        assert_eq!("00 99 88 77 66 55 44 33 22 11".parse(), Ok(Key(KEY)));
+
+        // Either case, and surrounding or repeated whitespace, are fine.
+        assert_eq!(
+            "0A0b0C0d0E0f00000000".parse(),
+            Ok(Key([0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0, 0, 0, 0]))
+        );
+        assert_eq!("  00 99  88 77 66 55 44 33 22\t11\n".parse(), Ok(Key(KEY)));
+        assert_eq!(" 00:99:88:77:66:55:44:33:22:11 ".parse(), Ok(Key(KEY)));
+    }
+
+    #[test]
+    fn test_key_from_str_invalid() {
+        // The wrong number of bytes.
+        assert_eq!("".parse::<Key>(), Err(Error::InvalidKeyLength));
+        assert_eq!(
+            "009988776655443322".parse::<Key>(),
+            Err(Error::InvalidKeyLength)
+        );
+        assert_eq!(
+            "0099887766554433221100".parse::<Key>(),
+            Err(Error::InvalidKeyLength)
+        );
+        assert_eq!(
+            "00:99:88:77:66:55:44:33:22".parse::<Key>(),
+            Err(Error::InvalidKeyLength)
+        );
+        assert_eq!(
+            "00 99 88 77 66 55 44 33 22 11 00".parse::<Key>(),
+            Err(Error::InvalidKeyLength)
+        );
+
+        // Bytes that aren't two hex digits.
+        assert_eq!(
+            "0099887766554433221g".parse::<Key>(),
+            Err(Error::InvalidHex)
+        );
+        assert_eq!("0099887766554433221".parse::<Key>(), Err(Error::InvalidHex));
+        assert_eq!(
+            "0:99:88:77:66:55:44:33:22:11".parse::<Key>(),
+            Err(Error::InvalidHex)
+        );
+        assert_eq!(
+            "00::99:88:77:66:55:44:33:22:11".parse::<Key>(),
+            Err(Error::InvalidHex)
+        );
+        assert_eq!(
+            "00:99 88:77:66:55:44:33:22:11".parse::<Key>(),
+            Err(Error::InvalidHex)
+        );
+        assert_eq!(
+            "0x00998877665544332211".parse::<Key>(),
+            Err(Error::InvalidHex)
+        );
+    }
+
+    #[test]
+    fn test_key_fingerprint() {
+        assert_eq!(key_fingerprint(&KEY), 0x2ad8b922fa94b15e);
+
+        // It isn't the encryption of anything obvious under the key itself.
+        assert_ne!(key_fingerprint(&KEY), encrypt_block(0u64, KEY));
+        assert_ne!(key_fingerprint(&KEY), encrypt_block(FINGERPRINT_TAG, KEY));
+
+        let same: Key = "00:99:88:77:66:55:44:33:22:11".parse().unwrap();
+        assert_eq!(key_fingerprint(&KEY), key_fingerprint(&same.0));
+
+        // Keys differing in a single bit have (almost surely) different fingerprints.
+        let mut other = KEY;
+        other[9] ^= 0x01;
+        assert_ne!(key_fingerprint(&KEY), key_fingerprint(&other));
+        assert_ne!(key_fingerprint(&[0; 10]), key_fingerprint(&[0xff; 10]));
+    }
+
+    #[test]
+    fn test_encrypt_block_shared() {
+        // Two shares whose XOR is the NIST key.
+        let a = [0x5a, 0x0f, 0xf0, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde];
+        let mut b = KEY;
+        for (x, y) in b.iter_mut().zip(a.iter()) {
+            *x ^= y;
+        }
+
+        assert_eq!(
+            encrypt_block_shared(0x33221100ddccbbaa, &[a, b]).unwrap(),
+            0x2587cae27a12d300
+        );
+
+        // One share is just the key; a share that's all zeroes changes nothing;
+        // and no one share alone is the key.
+        assert_eq!(
+            encrypt_block_shared(0x33221100ddccbbaa, &[KEY]).unwrap(),
+            0x2587cae27a12d300
+        );
+        assert_eq!(
+            encrypt_block_shared(0x33221100ddccbbaa, &[a, [0; 10], b]).unwrap(),
+            0x2587cae27a12d300
+        );
+        assert_ne!(
+            encrypt_block_shared(0x33221100ddccbbaa, &[a]).unwrap(),
+            0x2587cae27a12d300
+        );
+    }
+
+    #[test]
+    fn test_encrypt_block_shared_empty() {
+        assert_eq!(encrypt_block_shared(0u64, &[]), Err(Error::NoKeyShares));
+    }
+
+    #[test]
+    fn test_is_weak_key() {
+        assert!(is_weak_key(&[0; 10]));
+        assert!(is_weak_key(&[0x55; 10]));
+        assert!(is_weak_key(&[0xff; 10]));
+        assert!(is_weak_key(&[
+            0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd, 0xab, 0xcd
+        ]));
+
+        assert!(!is_weak_key(&KEY));
+        assert!(!is_weak_key(&[
+            0xa5, 0x3c, 0x91, 0x0e, 0x77, 0xd2, 0x48, 0x1b, 0xe6, 0x5f
+        ]));
+        assert!(!is_weak_key(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 1]));
+
+        // Period 5 doesn't collapse the schedule, since 4 isn't a multiple of 5.
+        assert!(!is_weak_key(b"abcdeabcde"));
+    }
+
+    #[test]
+    fn test_weak_key_schedule() {
+        use super::super::schedule::RoundKeys;
+
+        // For weak keys, every round uses the same round key.
+        for key in [[0x55; 10], *b"ababababab"].iter() {
+            assert!(is_weak_key(key));
+            for step in 0..32 {
+                assert_eq!(key.round_key(step), key.round_key(0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_debug() {
+        assert_eq!(format!("{:?}", Key(KEY)), "Key(..)");
+    }
+}
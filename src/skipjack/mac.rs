@@ -0,0 +1,416 @@
+//! Message authentication codes built on Skipjack.
+
+use core::borrow::Borrow;
+use core::fmt;
+
+use super::key::encrypt_block_masked;
+use super::util::ct_eq;
+use super::{encrypt_block, gf, Cipher, Error, BLOCK_SIZE};
+
+// `wc_mac` derives its hash key by encrypting the all-zero block under the key
+// XOR'ed with this mask, which nothing else uses.
+const WC_HASH_KEY_MASK: [u8; 10] = *b"wc-mac-key";
+
+// Derives the two CMAC subkeys, K1 and K2, from the secret key.
+pub(crate) fn subkeys(key: &[u8; 10]) -> (u64, u64) {
+    let l = encrypt_block(0, key);
+    let k1 = gf::dbl(l);
+    let k2 = gf::dbl(k1);
+
+    (k1, k2)
+}
+
+/// Computes the CMAC (NIST SP 800-38B) of the given data under the given key.
+///
+/// # Arguments
+///
+/// * `data` - The data to authenticate
+/// * `key` - The secret key to authenticate with
+pub fn cmac(data: &[u8], key: impl Borrow<[u8; 10]>) -> u64 {
+    let key = key.borrow();
+    let (k1, k2) = subkeys(key);
+
+    // CMAC is CBC-MAC, except that the final block gets special treatment.
+    // We split off that final block, which may be partial or even empty.
+    let last_len = match data.len() % BLOCK_SIZE {
+        0 if !data.is_empty() => BLOCK_SIZE,
+        n => n,
+    };
+    let (head, last) = data.split_at(data.len() - last_len);
+
+    let mut state = 0;
+    for chunk in head.chunks_exact(BLOCK_SIZE) {
+        let mut block = [0u8; BLOCK_SIZE];
+        block.copy_from_slice(chunk);
+        state = encrypt_block(state ^ u64::from_be_bytes(block), key);
+    }
+
+    encrypt_block(state ^ last_block(last, k1, k2), key)
+}
+
+/// Computes a Wegman-Carter MAC of the given blocks, under the given nonce and key.
+///
+/// The blocks are hashed with a polynomial over GF(2^64), as in GCM's GHASH:
+/// each block in turn is XOR'ed into an accumulator, which is then multiplied
+/// by the hash key `H`, and the number of blocks is folded in last (so that
+/// leading zero blocks count). The tag is the hash, XOR'ed with
+/// `encrypt_block(nonce, key)`. This is far fewer block encryptions than CMAC,
+/// which encrypts every block.
+///
+/// `H` is the encryption of the all-zero block under the key XOR'ed with a
+/// fixed mask, which no other mode or MAC here uses: it isn't CMAC's `L` or a
+/// CTR keystream block, so using the same key elsewhere doesn't give it away.
+///
+/// **The nonce must never repeat under the same key.** Two tags with the same
+/// nonce give away the XOR of two hashes, from which the hash key (and then
+/// forgeries) can be recovered. Nonce 0 is reserved, so that the tag is never
+/// masked with `encrypt_block(0, key)`, which CMAC and CTR also compute. Even
+/// with unique nonces, a forgery succeeds with probability about `n / 2^64`
+/// for an `n`-block message: fine for short messages, but Skipjack's 64-bit
+/// block leaves little margin.
+///
+/// Returns `Error::ReservedNonce` if `nonce` is 0.
+///
+/// # Arguments
+///
+/// * `data` - The blocks to authenticate
+/// * `nonce` - A nonzero value, unique to this message (under this key)
+/// * `key` - The secret key to authenticate with
+pub fn wc_mac(data: &[u64], nonce: u64, key: impl Borrow<[u8; 10]>) -> Result<u64, Error> {
+    if nonce == 0 {
+        return Err(Error::ReservedNonce);
+    }
+
+    let h = encrypt_block_masked(0, key.borrow(), &WC_HASH_KEY_MASK);
+
+    let mut hash = 0;
+    for &block in data.iter() {
+        hash = gf::mul(hash ^ block, h);
+    }
+    hash = gf::mul(hash ^ data.len() as u64, h);
+
+    Ok(hash ^ encrypt_block(nonce, key))
+}
+
+/// A MAC tag, possibly truncated to fewer than 8 bytes.
+///
+/// This deliberately doesn't implement `PartialEq`: compare tags with `verify`,
+/// which runs in constant time.
+#[derive(Clone, Copy, Debug)]
+pub struct Tag {
+    bytes: [u8; 8],
+    len: usize,
+}
+
+impl Tag {
+    /// Returns the tag's bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Returns the length of the tag, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the tag is empty. Tags are never empty; this is here
+    /// for consistency with `len`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Compares the tag with a received one, in constant time.
+    ///
+    /// Only as many bytes as the tag holds are compared, and a received tag of
+    /// any other length never matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `received` - The tag to compare against
+    pub fn verify(&self, received: &[u8]) -> bool {
+        ct_eq(self.as_bytes(), received)
+    }
+}
+
+impl AsRef<[u8]> for Tag {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+/// Computes the CMAC of the given data, truncated to `tag_len_bytes` bytes.
+///
+/// The tag is the most significant `tag_len_bytes` bytes of the full, big-endian
+/// 64-bit tag, as in NIST SP 800-38B. `tag_len_bytes` must be between 1 and 8;
+/// otherwise, `Error::InvalidTagLength` is returned.
+///
+/// Truncation weakens the tag: a forger who guesses a `t`-bit tag succeeds with
+/// probability 2^-t per attempt, so a 32-bit tag can be forged in around 2^32
+/// tries, which is well within reach of anyone who can submit messages quickly.
+/// SP 800-38B recommends at least 64 bits where possible, which for Skipjack
+/// means no truncation at all. Short tags only make sense when the number of
+/// verification attempts is strictly limited.
+///
+/// # Arguments
+///
+/// * `data` - The data to authenticate
+/// * `key` - The secret key to authenticate with
+/// * `tag_len_bytes` - The length of the tag, in bytes (1 to 8)
+pub fn cmac_truncated(
+    data: &[u8],
+    key: impl Borrow<[u8; 10]>,
+    tag_len_bytes: usize,
+) -> Result<Tag, Error> {
+    if !(1..=8).contains(&tag_len_bytes) {
+        return Err(Error::InvalidTagLength);
+    }
+
+    Ok(Tag {
+        bytes: cmac(data, key).to_be_bytes(),
+        len: tag_len_bytes,
+    })
+}
+
+/// Checks a (possibly truncated) CMAC tag against the given data.
+///
+/// The tag's length determines the truncation, as in `cmac_truncated`, and
+/// the comparison is constant time. Returns `Error::InvalidTagLength` if the
+/// tag isn't 1 to 8 bytes long, or `Error::AuthenticationFailed` if it doesn't
+/// match.
+///
+/// # Arguments
+///
+/// * `data` - The data to authenticate
+/// * `key` - The secret key to authenticate with
+/// * `tag` - The received tag
+pub fn verify_cmac(data: &[u8], key: impl Borrow<[u8; 10]>, tag: &[u8]) -> Result<(), Error> {
+    if cmac_truncated(data, key, tag.len())?.verify(tag) {
+        Ok(())
+    } else {
+        Err(Error::AuthenticationFailed)
+    }
+}
+
+// Returns CMAC's final block, given its (possibly partial, or empty) contents.
+fn last_block(last: &[u8], k1: u64, k2: u64) -> u64 {
+    // A complete final block is masked with K1. Anything else is padded with
+    // a single 1 bit followed by 0 bits, and masked with K2.
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..last.len()].copy_from_slice(last);
+    if last.len() == BLOCK_SIZE {
+        u64::from_be_bytes(block) ^ k1
+    } else {
+        block[last.len()] = 0x80;
+        u64::from_be_bytes(block) ^ k2
+    }
+}
+
+/// An incremental CMAC computation, for data that arrives in pieces.
+///
+/// Feeding data to `update` in any number of pieces, then calling `finalize`,
+/// gives the same tag as calling `cmac` on all of the data at once.
+#[derive(Clone)]
+pub struct CmacContext {
+    cipher: Cipher,
+    k1: u64,
+    k2: u64,
+    // The CBC-MAC state over every block before `buffer`.
+    state: u64,
+    // Data that hasn't been processed yet. This can be a complete block: we
+    // can't process a block until we know it isn't the last one, since the last
+    // block is treated differently.
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+}
+
+impl CmacContext {
+    /// Creates a new CMAC computation with the given secret key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to authenticate with
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        let (k1, k2) = subkeys(key.borrow());
+
+        CmacContext {
+            cipher: Cipher::new(key),
+            k1,
+            k2,
+            state: 0,
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+        }
+    }
+
+    /// Adds the given data to the message being authenticated.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The next piece of the message
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // There's more data, so a full buffer isn't the last block.
+            if self.buffered == BLOCK_SIZE {
+                let block = u64::from_be_bytes(self.buffer);
+                self.state = self.cipher.encrypt_block(self.state ^ block);
+                self.buffered = 0;
+            }
+
+            let take = (BLOCK_SIZE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+        }
+    }
+
+    /// Finishes the computation, returning the tag.
+    pub fn finalize(self) -> u64 {
+        let last = last_block(&self.buffer[..self.buffered], self.k1, self.k2);
+
+        self.cipher.encrypt_block(self.state ^ last)
+    }
+}
+
+impl fmt::Debug for CmacContext {
+    // Never include the key (or anything derived from it) in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CmacContext").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_cmac() {
+        // Empty, partial, exactly one block, and several blocks plus a partial block.
+        assert_eq!(cmac(b"", KEY), 0x411d478c906c12a3);
+        assert_eq!(cmac(b"abc", KEY), 0xc1cd25fce92149cd);
+        assert_eq!(cmac(b"abcdefgh", KEY), 0x0c94c748933148e6);
+        assert_eq!(
+            cmac(b"The quick brown fox jumps over the lazy dog", KEY),
+            0x977d2ce51272c8bc
+        );
+    }
+
+    #[test]
+    fn test_wc_mac() {
+        let data = [0x33221100ddccbbaa, 0x0123456789abcdef, 0];
+        let tag = wc_mac(&data, 1, KEY).unwrap();
+
+        // With one block, the hash is easy to work out by hand.
+        let mut hash_key = KEY;
+        for (k, m) in hash_key.iter_mut().zip(b"wc-mac-key".iter()) {
+            *k ^= m;
+        }
+        let h = encrypt_block(0u64, hash_key);
+        let expected = gf::mul(gf::mul(data[0], h) ^ 1, h) ^ encrypt_block(1u64, KEY);
+        assert_eq!(wc_mac(&data[..1], 1, KEY), Ok(expected));
+
+        // The hash key isn't the all-zero block's encryption under the key,
+        // which CMAC and CTR also compute.
+        assert_ne!(h, encrypt_block(0u64, KEY));
+
+        // Deterministic...
+        assert_eq!(wc_mac(&data, 1, KEY), Ok(tag));
+
+        // ...but any change to a block, the nonce, or the length changes the tag.
+        let mut changed = data;
+        changed[1] ^= 0x80;
+        assert_ne!(wc_mac(&changed, 1, KEY), Ok(tag));
+        assert_ne!(wc_mac(&data, 2, KEY), Ok(tag));
+        assert_ne!(wc_mac(&data[..2], 1, KEY), Ok(tag));
+        assert_ne!(
+            wc_mac(&[0, 0x33221100ddccbbaa, 0x0123456789abcdef, 0], 1, KEY),
+            Ok(tag)
+        );
+    }
+
+    #[test]
+    fn test_wc_mac_zero_nonce() {
+        assert_eq!(wc_mac(&[1, 2, 3], 0, KEY), Err(Error::ReservedNonce));
+    }
+
+    #[test]
+    fn test_cmac_truncated() {
+        let data = b"abc";
+
+        let tag = cmac_truncated(data, KEY, 8).unwrap();
+        assert_eq!(tag.as_bytes(), &0xc1cd25fce92149cdu64.to_be_bytes());
+
+        // A truncated tag is the most significant bytes of the full tag.
+        let tag = cmac_truncated(data, KEY, 4).unwrap();
+        assert_eq!(tag.as_bytes(), &[0xc1, 0xcd, 0x25, 0xfc]);
+        assert_eq!(tag.len(), 4);
+
+        assert_eq!(
+            cmac_truncated(data, KEY, 0).unwrap_err(),
+            Error::InvalidTagLength
+        );
+        assert_eq!(
+            cmac_truncated(data, KEY, 9).unwrap_err(),
+            Error::InvalidTagLength
+        );
+    }
+
+    #[test]
+    fn test_verify_cmac() {
+        let data = b"abc";
+
+        assert_eq!(verify_cmac(data, KEY, &[0xc1, 0xcd, 0x25, 0xfc]), Ok(()));
+        assert_eq!(
+            verify_cmac(data, KEY, &0xc1cd25fce92149cdu64.to_be_bytes()),
+            Ok(())
+        );
+
+        assert_eq!(
+            verify_cmac(data, KEY, &[0xc1, 0xcd, 0x25, 0xfd]),
+            Err(Error::AuthenticationFailed)
+        );
+        assert_eq!(
+            verify_cmac(b"abd", KEY, &[0xc1, 0xcd, 0x25, 0xfc]),
+            Err(Error::AuthenticationFailed)
+        );
+        assert_eq!(verify_cmac(data, KEY, &[]), Err(Error::InvalidTagLength));
+        assert_eq!(
+            verify_cmac(data, KEY, &[0; 9]),
+            Err(Error::InvalidTagLength)
+        );
+
+        // A truncated tag only matches received tags of the same length.
+        let tag = cmac_truncated(data, KEY, 4).unwrap();
+        assert!(tag.verify(&[0xc1, 0xcd, 0x25, 0xfc]));
+        assert!(!tag.verify(&[0xc1, 0xcd, 0x25]));
+        assert!(!tag.verify(&0xc1cd25fce92149cdu64.to_be_bytes()));
+    }
+
+    #[test]
+    fn test_cmac_context() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+
+        // Every way of splitting the data in two, including the empty pieces
+        // and splits exactly on block boundaries.
+        for split in 0..=data.len() {
+            let mut context = CmacContext::new(KEY);
+            context.update(&data[..split]);
+            context.update(&data[split..]);
+            assert_eq!(context.finalize(), cmac(data, KEY), "split at {}", split);
+        }
+
+        // A byte at a time, with empty updates in between.
+        for len in 0..=data.len() {
+            let mut context = CmacContext::new(KEY);
+            for byte in data[..len].chunks(1) {
+                context.update(byte);
+                context.update(b"");
+            }
+            assert_eq!(context.finalize(), cmac(&data[..len], KEY));
+        }
+
+        // No updates at all is the empty message.
+        assert_eq!(CmacContext::new(KEY).finalize(), 0x411d478c906c12a3);
+    }
+}
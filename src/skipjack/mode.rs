@@ -0,0 +1,158 @@
+//! Choosing a mode of operation at runtime.
+//!
+//! Each mode has its own module, with functions suited to that mode (blocks for
+//! CBC, bytes for the stream modes). `apply` puts them all behind one signature,
+//! for tools that read the mode from a configuration file or a message header.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::convert::TryInto;
+
+use super::cbc::{cbc_step_decrypt, cbc_step_encrypt};
+use super::{cfb8, ctr, decrypt_block, encrypt_block, ofb, Direction, Error, BLOCK_SIZE};
+
+/// A mode of operation, for `apply`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Electronic codebook: each block is encrypted independently. The IV is ignored.
+    Ecb,
+    /// Cipher block chaining (see `cbc`).
+    Cbc,
+    /// Counter mode, with the IV as the nonce (see `ctr`).
+    Ctr,
+    /// 8-bit cipher feedback (see `cfb8`).
+    Cfb8,
+    /// Output feedback (see `ofb`).
+    Ofb,
+}
+
+/// Encrypts or decrypts `data` with the given mode, returning the result.
+///
+/// ECB and CBC operate on whole blocks, and don't pad: `data` must be a multiple
+/// of the block size, or `Error::BufferNotBlockAligned` is returned. The other
+/// modes accept data of any length. Blocks are read big-endian, as everywhere
+/// else in this crate.
+///
+/// # Arguments
+///
+/// * `mode` - The mode of operation to use
+/// * `direction` - Whether to encrypt or decrypt
+/// * `data` - The data to encrypt or decrypt
+/// * `iv` - The initialization vector (or, for CTR, the nonce)
+/// * `key` - The secret key to encrypt or decrypt with
+pub fn apply(
+    mode: Mode,
+    direction: Direction,
+    data: &[u8],
+    iv: u64,
+    key: impl Borrow<[u8; 10]>,
+) -> Result<Vec<u8>, Error> {
+    let key = key.borrow();
+    let mut output = data.to_vec();
+
+    match mode {
+        Mode::Ecb | Mode::Cbc if !data.len().is_multiple_of(BLOCK_SIZE) => {
+            return Err(Error::BufferNotBlockAligned);
+        }
+        Mode::Ecb => {
+            for chunk in output.chunks_exact_mut(BLOCK_SIZE) {
+                let block = u64::from_be_bytes(chunk.try_into().unwrap());
+                let result = match direction {
+                    Direction::Encrypt => encrypt_block(block, key),
+                    Direction::Decrypt => decrypt_block(block, key),
+                };
+                chunk.copy_from_slice(&result.to_be_bytes());
+            }
+        }
+        Mode::Cbc => {
+            let mut chain = iv.to_be_bytes();
+            for chunk in output.chunks_exact_mut(BLOCK_SIZE) {
+                let block = chunk.try_into().unwrap();
+                match direction {
+                    Direction::Encrypt => cbc_step_encrypt(block, &mut chain, key),
+                    Direction::Decrypt => cbc_step_decrypt(block, &mut chain, key),
+                }
+            }
+        }
+        // The stream modes are their own inverses, except for CFB-8's feedback.
+        Mode::Ctr => ctr::apply_keystream(&mut output, iv, key),
+        Mode::Cfb8 => cfb8::transform_inplace(&mut output, iv, key, direction),
+        Mode::Ofb => ofb::apply_keystream(&mut output, iv, key),
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cbc::encrypt_cbc;
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    const MODES: [Mode; 5] = [Mode::Ecb, Mode::Cbc, Mode::Ctr, Mode::Cfb8, Mode::Ofb];
+
+    #[test]
+    fn test_roundtrip() {
+        let plaintext = b"three whole blocks, 24 b";
+
+        for &mode in MODES.iter() {
+            let ciphertext = apply(mode, Direction::Encrypt, plaintext, IV, KEY).unwrap();
+            assert_ne!(&ciphertext[..], &plaintext[..], "{:?}", mode);
+
+            let decrypted = apply(mode, Direction::Decrypt, &ciphertext, IV, KEY).unwrap();
+            assert_eq!(&decrypted[..], &plaintext[..], "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let plaintext = [
+            0x33, 0x22, 0x11, 0x00, 0xdd, 0xcc, 0xbb, 0xaa, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+
+        let ecb = apply(Mode::Ecb, Direction::Encrypt, &plaintext, IV, KEY).unwrap();
+        assert_eq!(ecb[..8], 0x2587cae27a12d300u64.to_be_bytes());
+
+        let cbc = apply(Mode::Cbc, Direction::Encrypt, &plaintext, IV, KEY).unwrap();
+        let expected = encrypt_cbc(&[0x33221100ddccbbaa, 0], IV, KEY);
+        assert_eq!(cbc[..8], expected[0].to_be_bytes());
+        assert_eq!(cbc[8..], expected[1].to_be_bytes());
+
+        let mut expected = plaintext;
+        ctr::apply_keystream(&mut expected, IV, KEY);
+        assert_eq!(
+            apply(Mode::Ctr, Direction::Encrypt, &plaintext, IV, KEY).unwrap(),
+            expected
+        );
+
+        let mut expected = plaintext;
+        cfb8::encrypt_inplace(&mut expected, IV, KEY);
+        assert_eq!(
+            apply(Mode::Cfb8, Direction::Encrypt, &plaintext, IV, KEY).unwrap(),
+            expected
+        );
+
+        let mut expected = plaintext;
+        ofb::apply_keystream(&mut expected, IV, KEY);
+        assert_eq!(
+            apply(Mode::Ofb, Direction::Encrypt, &plaintext, IV, KEY).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_unaligned() {
+        let data = b"not a whole block";
+
+        for &mode in MODES.iter() {
+            let result = apply(mode, Direction::Encrypt, data, IV, KEY);
+
+            match mode {
+                Mode::Ecb | Mode::Cbc => assert_eq!(result, Err(Error::BufferNotBlockAligned)),
+                _ => assert_eq!(result.unwrap().len(), data.len()),
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Output feedback (OFB) mode.
+//!
+//! OFB turns Skipjack into a stream cipher: the keystream is the IV, encrypted
+//! over and over again, and is XOR'ed with the data. Like CTR mode, encryption
+//! and decryption are the same operation, and the data needn't be padded.
+//!
+//! **Never use the same IV twice with the same key.** Doing so reuses the
+//! keystream, which reveals the XOR of the two plaintexts.
+
+use core::borrow::Borrow;
+
+use super::{Cipher, BLOCK_SIZE};
+
+/// Applies the OFB keystream for the given IV and key to `data`, in place.
+///
+/// The `i`th block of keystream is the IV encrypted `i + 1` times, in
+/// big-endian byte order. `data` does not need to be a multiple of the block
+/// size; any unused keystream from the final block is discarded.
+///
+/// # Arguments
+///
+/// * `data` - The data to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to generate the keystream with
+pub fn apply_keystream(data: &mut [u8], iv: u64, key: impl Borrow<[u8; 10]>) {
+    Cipher::new(key).apply_ofb_keystream(data, iv);
+}
+
+/// Applies the OFB keystream for the given IV and key to a `BytesMut`, in place.
+///
+/// This is `apply_keystream`, for buffers from the `bytes` crate, without a copy.
+///
+/// # Arguments
+///
+/// * `buf` - The data to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to generate the keystream with
+#[cfg(feature = "bytes")]
+pub fn apply_keystream_bytes(buf: &mut bytes::BytesMut, iv: u64, key: impl Borrow<[u8; 10]>) {
+    apply_keystream(buf, iv, key);
+}
+
+impl<M> Cipher<M> {
+    /// Applies the OFB keystream for the given IV to `data`, in place, like
+    /// `ofb::apply_keystream`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The data to encrypt or decrypt
+    /// * `iv` - The initialization vector
+    pub fn apply_ofb_keystream(&self, data: &mut [u8], iv: u64) {
+        let mut feedback = iv;
+
+        for chunk in data.chunks_mut(BLOCK_SIZE) {
+            feedback = self.encrypt_block(feedback);
+
+            for (byte, k) in chunk.iter_mut().zip(feedback.to_be_bytes().iter()) {
+                *byte ^= k;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_apply_keystream() {
+        let plaintext = b"sixteen byte msg and more";
+
+        let mut data = *plaintext;
+        apply_keystream(&mut data, IV, KEY);
+        assert_eq!(
+            data[..],
+            [
+                0xf0, 0x8e, 0x9a, 0xee, 0x7f, 0x58, 0xf5, 0xbc, 0x05, 0x05, 0x9a, 0xd6, 0xd1, 0xb7,
+                0x66, 0xbe, 0x12, 0xbd, 0xa8, 0xad, 0xc3, 0x3a, 0xc0, 0xf7, 0xf0,
+            ][..]
+        );
+
+        apply_keystream(&mut data, IV, KEY);
+        assert_eq!(&data, plaintext);
+    }
+
+    #[test]
+    fn test_cipher_methods() {
+        let plaintext = *b"sixteen byte msg and more";
+        let cipher = Cipher::new(KEY);
+
+        let mut expected = plaintext;
+        apply_keystream(&mut expected, IV, KEY);
+
+        let mut data = plaintext;
+        cipher.apply_ofb_keystream(&mut data, IV);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn test_apply_keystream_bytes() {
+        let plaintext = *b"sixteen byte msg and more";
+
+        let mut expected = plaintext;
+        apply_keystream(&mut expected, IV, KEY);
+
+        let mut buf = bytes::BytesMut::from(&plaintext[..]);
+        apply_keystream_bytes(&mut buf, IV, KEY);
+        assert_eq!(&buf[..], &expected[..]);
+
+        apply_keystream_bytes(&mut buf, IV, KEY);
+        assert_eq!(&buf[..], &plaintext[..]);
+    }
+}
@@ -0,0 +1,171 @@
+//! Propagating cipher block chaining (PCBC) mode.
+//!
+//! In PCBC, each plaintext block is XOR'ed with both the previous plaintext block
+//! and the previous ciphertext block before being encrypted. The first block is
+//! XOR'ed with the initialization vector instead.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use super::{Cipher, Direction};
+
+/// Encrypts the given blocks in PCBC mode.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt with
+pub fn encrypt(blocks: &[u64], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u64> {
+    transform(blocks, iv, key, Direction::Encrypt)
+}
+
+/// Decrypts the given blocks in PCBC mode.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to decrypt with
+pub fn decrypt(blocks: &[u64], iv: u64, key: impl Borrow<[u8; 10]>) -> Vec<u64> {
+    transform(blocks, iv, key, Direction::Decrypt)
+}
+
+/// Encrypts or decrypts the given blocks in PCBC mode, depending on `direction`.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt or decrypt
+/// * `iv` - The initialization vector
+/// * `key` - The secret key to encrypt or decrypt with
+/// * `direction` - Whether to encrypt or decrypt
+pub fn transform(
+    blocks: &[u64],
+    iv: u64,
+    key: impl Borrow<[u8; 10]>,
+    direction: Direction,
+) -> Vec<u64> {
+    transform_with(&Cipher::new(key), blocks, iv, direction)
+}
+
+// PCBC itself, shared by the free functions and the `Cipher` methods.
+fn transform_with<M>(
+    cipher: &Cipher<M>,
+    blocks: &[u64],
+    iv: u64,
+    direction: Direction,
+) -> Vec<u64> {
+    // The feedback term starts as the IV, and becomes the XOR of the previous
+    // plaintext and ciphertext blocks after each block is processed.
+    let mut feedback = iv;
+
+    match direction {
+        Direction::Encrypt => blocks
+            .iter()
+            .map(|&plaintext| {
+                let ciphertext = cipher.encrypt_block(plaintext ^ feedback);
+                feedback = plaintext ^ ciphertext;
+                ciphertext
+            })
+            .collect(),
+        Direction::Decrypt => blocks
+            .iter()
+            .map(|&ciphertext| {
+                let plaintext = cipher.decrypt_block(ciphertext) ^ feedback;
+                feedback = plaintext ^ ciphertext;
+                plaintext
+            })
+            .collect(),
+    }
+}
+
+impl<M> Cipher<M> {
+    /// Encrypts the given blocks in PCBC mode, like `pcbc::encrypt`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to encrypt
+    /// * `iv` - The initialization vector
+    pub fn encrypt_pcbc(&self, blocks: &[u64], iv: u64) -> Vec<u64> {
+        transform_with(self, blocks, iv, Direction::Encrypt)
+    }
+
+    /// Decrypts the given blocks in PCBC mode, like `pcbc::decrypt`.
+    ///
+    /// # Arguments
+    ///
+    /// * `blocks` - The blocks to decrypt
+    /// * `iv` - The initialization vector
+    pub fn decrypt_pcbc(&self, blocks: &[u64], iv: u64) -> Vec<u64> {
+        transform_with(self, blocks, iv, Direction::Decrypt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    #[test]
+    fn test_roundtrip() {
+        let plaintext = [
+            0x33221100ddccbbaa,
+            0,
+            0,
+            0xffffffffffffffff,
+            0x33221100ddccbbaa,
+        ];
+
+        let ciphertext = encrypt(&plaintext, IV, KEY);
+
+        // Identical plaintext blocks don't produce identical ciphertext blocks.
+        assert_ne!(ciphertext[1], ciphertext[2]);
+        assert_ne!(ciphertext[0], ciphertext[4]);
+
+        assert_eq!(decrypt(&ciphertext, IV, KEY), plaintext);
+    }
+
+    #[test]
+    fn test_swapped_blocks() {
+        let plaintext = [1, 2, 3, 4, 5, 6];
+
+        let mut ciphertext = encrypt(&plaintext, IV, KEY);
+        ciphertext.swap(1, 2);
+
+        // Swapping two adjacent ciphertext blocks garbles those two blocks, but
+        // the feedback term after them is unchanged, so every subsequent block
+        // still decrypts correctly.
+        let decrypted = decrypt(&ciphertext, IV, KEY);
+        assert_eq!(decrypted[0], plaintext[0]);
+        assert_ne!(decrypted[1..3], plaintext[1..3]);
+        assert_eq!(decrypted[3..], plaintext[3..]);
+    }
+
+    #[test]
+    fn test_cipher_methods() {
+        let plaintext = [0x33221100ddccbbaa, 0, 0, 0xffffffffffffffff];
+        let cipher = Cipher::new(KEY);
+
+        let ciphertext = cipher.encrypt_pcbc(&plaintext, IV);
+        assert_eq!(ciphertext, encrypt(&plaintext, IV, KEY));
+        assert_eq!(cipher.decrypt_pcbc(&ciphertext, IV), plaintext);
+    }
+
+    #[test]
+    fn test_transform() {
+        let plaintext = [0x33221100ddccbbaa, 0, 0, 0xffffffffffffffff];
+        let ciphertext = encrypt(&plaintext, IV, KEY);
+
+        assert_eq!(
+            transform(&plaintext, IV, KEY, Direction::Encrypt),
+            ciphertext
+        );
+        assert_eq!(
+            transform(&ciphertext, IV, KEY, Direction::Decrypt),
+            plaintext
+        );
+        assert_eq!(decrypt(&ciphertext, IV, KEY), plaintext);
+    }
+}
@@ -0,0 +1,152 @@
+//! The four stepping rules, for assembling Skipjack by hand.
+//!
+//! These are the same rules that `encrypt_block` and `decrypt_block` use,
+//! exposed on their own so that the cipher's structure can be rebuilt (and
+//! studied) one round at a time. `Words` offers the same operations as methods.
+//!
+//! # The counter
+//!
+//! Every rule takes the round counter by mutable reference. The specification
+//! numbers the rounds from 1 to 32, and the counter is that number: each rule
+//! XORs it into the state, and uses `counter - 1` (the "step", numbered from 0)
+//! to choose which four key bytes rule G consumes.
+//!
+//! After running, rules A and B increment the counter, and rules A' and B'
+//! decrement it. So encryption starts with the counter at 1 and applies:
+//!
+//! * rule A for rounds 1 through 8,
+//! * rule B for rounds 9 through 16,
+//! * rule A for rounds 17 through 24,
+//! * rule B for rounds 25 through 32,
+//!
+//! leaving the counter at 33. (`ROUND_SCHEDULE` lists the rule for each round.)
+//! Decryption starts with the counter at 32 and runs the same schedule
+//! backwards, with B' for rounds 32 through 25, A' for rounds 24 through 17,
+//! and so on, leaving the counter at 0.
+//!
+//! The counter must be between 1 and 32 when a rule is applied. Each rule
+//! checks this, and panics otherwise, like `encrypt_block_with_fault` does for
+//! a fault outside the cipher.
+
+/// Applies a single round of rule A to `words`, then increments `counter`.
+///
+/// # Arguments
+///
+/// * `words` - The state, as four 16-bit words (Word 1 first)
+/// * `counter` - The round counter (1 to 32)
+/// * `key` - The secret key
+///
+/// # Panics
+///
+/// Panics if `counter` is 0 or greater than 32.
+pub fn rule_a(words: &mut [u16; 4], counter: &mut u16, key: &[u8; 10]) {
+    super::check_counter(*counter);
+    super::rule_a(words, counter, key);
+}
+
+/// Applies a single round of rule B to `words`, then increments `counter`.
+///
+/// # Arguments
+///
+/// * `words` - The state, as four 16-bit words (Word 1 first)
+/// * `counter` - The round counter (1 to 32)
+/// * `key` - The secret key
+///
+/// # Panics
+///
+/// Panics if `counter` is 0 or greater than 32.
+pub fn rule_b(words: &mut [u16; 4], counter: &mut u16, key: &[u8; 10]) {
+    super::check_counter(*counter);
+    super::rule_b(words, counter, key);
+}
+
+/// Applies a single round of rule A' (the inverse of rule A) to `words`, then
+/// decrements `counter`.
+///
+/// # Arguments
+///
+/// * `words` - The state, as four 16-bit words (Word 1 first)
+/// * `counter` - The round counter (1 to 32)
+/// * `key` - The secret key
+///
+/// # Panics
+///
+/// Panics if `counter` is 0 or greater than 32.
+pub fn rule_a_inv(words: &mut [u16; 4], counter: &mut u16, key: &[u8; 10]) {
+    super::check_counter(*counter);
+    super::rule_a_inv(words, counter, key);
+}
+
+/// Applies a single round of rule B' (the inverse of rule B) to `words`, then
+/// decrements `counter`.
+///
+/// # Arguments
+///
+/// * `words` - The state, as four 16-bit words (Word 1 first)
+/// * `counter` - The round counter (1 to 32)
+/// * `key` - The secret key
+///
+/// # Panics
+///
+/// Panics if `counter` is 0 or greater than 32.
+pub fn rule_b_inv(words: &mut [u16; 4], counter: &mut u16, key: &[u8; 10]) {
+    super::check_counter(*counter);
+    super::rule_b_inv(words, counter, key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_encrypt_by_hand() {
+        let mut words = [0x3322, 0x1100, 0xddcc, 0xbbaa];
+        let mut counter = 1;
+
+        for _ in 0..2 {
+            for _ in 0..8 {
+                rule_a(&mut words, &mut counter, &KEY);
+            }
+            for _ in 0..8 {
+                rule_b(&mut words, &mut counter, &KEY);
+            }
+        }
+
+        assert_eq!(words, [0x2587, 0xcae2, 0x7a12, 0xd300]);
+        assert_eq!(counter, 33);
+    }
+
+    #[test]
+    fn test_decrypt_by_hand() {
+        let mut words = [0x2587, 0xcae2, 0x7a12, 0xd300];
+        let mut counter = 32;
+
+        for _ in 0..2 {
+            for _ in 0..8 {
+                rule_b_inv(&mut words, &mut counter, &KEY);
+            }
+            for _ in 0..8 {
+                rule_a_inv(&mut words, &mut counter, &KEY);
+            }
+        }
+
+        assert_eq!(words, [0x3322, 0x1100, 0xddcc, 0xbbaa]);
+        assert_eq!(counter, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "round counter must be 1 to 32")]
+    fn test_rule_after_decryption() {
+        // Decryption leaves the counter at 0, which names no round.
+        rule_b_inv(&mut [0; 4], &mut 0, &KEY);
+    }
+
+    #[test]
+    #[should_panic(expected = "round counter must be 1 to 32")]
+    fn test_rule_after_encryption() {
+        // Encryption leaves the counter at 33, one past the last round.
+        rule_a(&mut [0; 4], &mut 33, &KEY);
+    }
+}
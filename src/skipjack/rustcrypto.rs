@@ -0,0 +1,249 @@
+//! Implementations of the [RustCrypto](https://github.com/RustCrypto) `cipher` traits.
+//!
+//! `SkipjackCtr` is Skipjack in CTR mode, as a `StreamCipher` and
+//! `StreamCipherSeek`, so that it can be used with code that's generic over
+//! stream ciphers. Its keystream is identical to `ctr::apply_keystream`'s.
+
+use core::borrow::Borrow;
+use core::convert::TryFrom;
+use core::fmt;
+
+use cipher::consts::{U10, U8};
+use cipher::inout::InOutBuf;
+use cipher::{
+    Iv, IvSizeUser, Key, KeyIvInit, KeySizeUser, OverflowError, SeekNum, StreamCipher,
+    StreamCipherError, StreamCipherSeek,
+};
+
+use super::{Cipher, BLOCK_SIZE};
+
+// The length of the keystream, in blocks: one for each 64-bit counter value.
+const KEYSTREAM_BLOCKS: u128 = 1 << 64;
+
+/// Skipjack in CTR mode, as a RustCrypto stream cipher.
+///
+/// Block `i` of the keystream is the encryption of `nonce + i` (wrapping at
+/// `u64::MAX`), exactly as in `ctr::apply_keystream`. Unlike that function,
+/// though, this stops after 2^64 blocks, when the counter would repeat:
+/// applying or seeking past that point is an error.
+///
+/// **Never use the same nonce twice with the same key.**
+#[derive(Clone)]
+pub struct SkipjackCtr {
+    cipher: Cipher,
+    nonce: u64,
+    // The index of the keystream block containing the next byte, from 0 to
+    // 2^64 (at which point the keystream is exhausted).
+    block: u128,
+    // The position of the next byte within that block.
+    byte: usize,
+}
+
+impl SkipjackCtr {
+    /// Creates a new CTR keystream for the given key and nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to generate the keystream with
+    /// * `nonce` - The initial counter value
+    pub fn new(key: impl Borrow<[u8; 10]>, nonce: u64) -> Self {
+        SkipjackCtr {
+            cipher: Cipher::new(key),
+            nonce,
+            block: 0,
+            byte: 0,
+        }
+    }
+
+    // The number of bytes of keystream left.
+    fn remaining(&self) -> u128 {
+        (KEYSTREAM_BLOCKS - self.block) * BLOCK_SIZE as u128 - self.byte as u128
+    }
+}
+
+impl KeySizeUser for SkipjackCtr {
+    type KeySize = U10;
+}
+
+impl IvSizeUser for SkipjackCtr {
+    type IvSize = U8;
+}
+
+impl KeyIvInit for SkipjackCtr {
+    /// Creates a new CTR keystream. The IV is the nonce, big-endian.
+    fn new(key: &Key<Self>, iv: &Iv<Self>) -> Self {
+        let key = <&[u8; 10]>::try_from(&key[..]).expect("key is 10 bytes");
+        let mut nonce = [0u8; BLOCK_SIZE];
+        nonce.copy_from_slice(iv);
+
+        SkipjackCtr::new(key, u64::from_be_bytes(nonce))
+    }
+}
+
+impl StreamCipher for SkipjackCtr {
+    fn try_apply_keystream_inout(
+        &mut self,
+        mut buf: InOutBuf<'_, '_, u8>,
+    ) -> Result<(), StreamCipherError> {
+        // Check that there's enough keystream before touching anything.
+        if buf.len() as u128 > self.remaining() {
+            return Err(StreamCipherError);
+        }
+
+        while !buf.is_empty() {
+            // `block` is less than 2^64 here, since there's keystream left.
+            let counter = self.nonce.wrapping_add(self.block as u64);
+            let keystream = self.cipher.encrypt_block(counter).to_be_bytes();
+
+            // Use the rest of this block's keystream, or as much as we need.
+            let len = (BLOCK_SIZE - self.byte).min(buf.len());
+            let (mut chunk, rest) = buf.split_at(len);
+            chunk.xor_in2out(&keystream[self.byte..self.byte + len]);
+            buf = rest;
+
+            self.byte += len;
+            if self.byte == BLOCK_SIZE {
+                self.block += 1;
+                self.byte = 0;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl StreamCipherSeek for SkipjackCtr {
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        // `from_block_byte` counts the blocks that have been started, so a
+        // position partway through a block counts that block too.
+        let started = if self.byte == 0 {
+            self.block
+        } else {
+            self.block + 1
+        };
+
+        T::from_block_byte(started, self.byte as u8, BLOCK_SIZE as u8)
+    }
+
+    fn try_seek<T: SeekNum>(&mut self, pos: T) -> Result<(), StreamCipherError> {
+        // A byte position splits into the index of its block, and its
+        // position within that block.
+        let (block, byte): (u128, u8) = pos
+            .into_block_byte(BLOCK_SIZE as u8)
+            .map_err(|_| StreamCipherError)?;
+
+        // Seeking to the very end of the keystream is fine, but not past it.
+        if block > KEYSTREAM_BLOCKS || (block == KEYSTREAM_BLOCKS && byte != 0) {
+            return Err(StreamCipherError);
+        }
+
+        self.block = block;
+        self.byte = byte as usize;
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SkipjackCtr {
+    // Never include the key in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SkipjackCtr").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ctr::apply_keystream;
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const NONCE: u64 = 0x33221100ddccbbaa;
+
+    #[test]
+    fn test_matches_apply_keystream() {
+        let mut expected = [0u8; 100];
+        apply_keystream(&mut expected, NONCE, KEY);
+
+        // Apply in uneven pieces, to cross block boundaries mid-call.
+        let mut data = [0u8; 100];
+        let mut ctr = SkipjackCtr::new(KEY, NONCE);
+        for chunk in data.chunks_mut(7) {
+            ctr.apply_keystream(chunk);
+        }
+        assert_eq!(data[..], expected[..]);
+        assert_eq!(ctr.current_pos::<u64>(), 100);
+
+        // The first block of keystream is just the encrypted nonce.
+        assert_eq!(data[..8], 0x2587cae27a12d300u64.to_be_bytes());
+
+        // And via `KeyIvInit`, with the nonce as a big-endian IV.
+        let mut data = [0u8; 100];
+        let mut ctr = <SkipjackCtr as KeyIvInit>::new(&KEY.into(), &NONCE.to_be_bytes().into());
+        ctr.apply_keystream(&mut data);
+        assert_eq!(data[..], expected[..]);
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut expected = [0u8; 100];
+        apply_keystream(&mut expected, NONCE, KEY);
+
+        for pos in 0..=100 {
+            let mut ctr = SkipjackCtr::new(KEY, NONCE);
+            ctr.seek(pos as u64);
+            assert_eq!(ctr.current_pos::<u64>(), pos as u64);
+
+            let mut data = [0u8; 100];
+            ctr.apply_keystream(&mut data[pos..]);
+            assert_eq!(data[pos..], expected[pos..], "seek to {}", pos);
+            assert_eq!(ctr.current_pos::<u64>(), 100);
+
+            // Seeking backwards works too.
+            ctr.seek(0u32);
+            let mut data = [0u8; 100];
+            ctr.apply_keystream(&mut data);
+            assert_eq!(data[..], expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_seek_far() {
+        // Seeking to a block far into the keystream lands on the counter value
+        // `nonce + block`, wrapping around.
+        let block = u64::MAX - 1;
+        let mut ctr = SkipjackCtr::new(KEY, NONCE);
+        ctr.seek(block as u128 * 8 + 3);
+
+        let mut data = [0u8; 5];
+        ctr.apply_keystream(&mut data);
+
+        let keystream = Cipher::new(KEY)
+            .encrypt_block(NONCE.wrapping_add(block))
+            .to_be_bytes();
+        assert_eq!(data[..], keystream[3..]);
+    }
+
+    #[test]
+    fn test_end_of_keystream() {
+        let end = KEYSTREAM_BLOCKS * 8;
+
+        let mut ctr = SkipjackCtr::new(KEY, NONCE);
+        assert!(ctr.try_seek(end + 1).is_err());
+        assert!(ctr.try_seek(end).is_ok());
+        assert_eq!(ctr.current_pos::<u128>(), end);
+        assert!(ctr.try_apply_keystream(&mut [0u8; 1]).is_err());
+
+        // The last byte of keystream can be used, but nothing after it. A
+        // request that runs off the end leaves the data untouched.
+        ctr.seek(end - 2);
+        let mut data = [0u8; 3];
+        assert!(ctr.try_apply_keystream(&mut data).is_err());
+        assert_eq!(data, [0; 3]);
+        assert!(ctr.try_apply_keystream(&mut data[..2]).is_ok());
+        assert_ne!(data[..2], [0; 2]);
+        assert_eq!(ctr.current_pos::<u128>(), end);
+
+        // The position doesn't fit in a u64.
+        assert!(ctr.try_current_pos::<u64>().is_err());
+    }
+}
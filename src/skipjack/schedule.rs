@@ -0,0 +1,91 @@
+//! Sources of per-round key bytes for rules G and G'.
+//!
+//! Each step of Skipjack consumes four bytes of the secret key, chosen by the
+//! step number. The free functions look those bytes up in the secret key on
+//! every round, exactly as the specification describes. `Cipher` instead
+//! precomputes all 32 rounds' bytes once, so that each round only loads an
+//! array. Both go through `RoundKeys`, so the rules themselves are shared.
+
+use super::{key_indices, ROUNDS};
+
+/// Something that can supply the four key bytes used at each step.
+pub(crate) trait RoundKeys {
+    /// Returns the four key bytes used by G (or G') at the given step,
+    /// in the order that G consumes them.
+    fn round_key(&self, step: u16) -> [u8; 4];
+}
+
+impl RoundKeys for [u8; 10] {
+    #[inline]
+    fn round_key(&self, step: u16) -> [u8; 4] {
+        let indices = key_indices(step);
+
+        // In tests, a lookup can record the key indices it used, so that the
+        // key schedule can be checked across a full encryption.
+        #[cfg(test)]
+        super::record_key_indices(indices);
+
+        [
+            self[indices[0]],
+            self[indices[1]],
+            self[indices[2]],
+            self[indices[3]],
+        ]
+    }
+}
+
+/// The key bytes for every step, computed once from a secret key.
+#[derive(Clone)]
+pub(crate) struct KeySchedule([[u8; 4]; ROUNDS]);
+
+impl KeySchedule {
+    /// Computes the key schedule for the given secret key.
+    pub(crate) fn new(key: &[u8; 10]) -> Self {
+        let mut schedule = [[0u8; 4]; ROUNDS];
+        for (step, round_key) in schedule.iter_mut().enumerate() {
+            let indices = key_indices(step as u16);
+            for (byte, &index) in round_key.iter_mut().zip(indices.iter()) {
+                *byte = key[index];
+            }
+        }
+
+        KeySchedule(schedule)
+    }
+}
+
+// With the `zeroize` feature, wipe the schedule (which is as good as the key)
+// when it's dropped.
+#[cfg(feature = "zeroize")]
+impl Drop for KeySchedule {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+impl RoundKeys for KeySchedule {
+    #[inline]
+    fn round_key(&self, step: u16) -> [u8; 4] {
+        self.0[step as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_key_schedule() {
+        let schedule = KeySchedule::new(&KEY);
+
+        for step in 0..ROUNDS as u16 {
+            assert_eq!(schedule.round_key(step), KEY.round_key(step));
+        }
+
+        // The key is consumed as a continuous cycle, four bytes per step.
+        assert_eq!(schedule.round_key(0), [0x00, 0x99, 0x88, 0x77]);
+        assert_eq!(schedule.round_key(2), [0x22, 0x11, 0x00, 0x99]);
+        assert_eq!(schedule.round_key(31), [0x66, 0x55, 0x44, 0x33]);
+    }
+}
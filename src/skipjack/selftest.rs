@@ -0,0 +1,56 @@
+//! Checking, at runtime, that the cipher works.
+//!
+//! A self-test doesn't find bugs in the implementation (that's what the tests
+//! are for): it finds the implementation damaged after the fact, by a bad
+//! build, corrupted memory, or faulty hardware. Integrators who need that
+//! assurance can run one at startup.
+
+use super::util::SplitMix64;
+use super::{decrypt_block, encrypt_block, Cipher, Error};
+
+/// Encrypts and decrypts a pseudorandom block under a pseudorandom key, both
+/// derived from `seed`, and checks that the round trip works.
+///
+/// Unlike a fixed test vector, a different seed (say, the boot count or the
+/// time) exercises different parts of the F table and key schedule each time
+/// it's run. The key and block come from a tiny non-cryptographic PRNG: they
+/// only need to vary, not to be secret.
+///
+/// Returns `Error::SelfTestFailed` if decryption doesn't undo encryption, if
+/// `Cipher` and `encrypt_block` disagree, or if the ciphertext equals the
+/// plaintext. (A working cipher does the last with probability 2^-64, so it's
+/// far more likely to mean that encryption did nothing at all.)
+///
+/// # Arguments
+///
+/// * `seed` - The seed to derive the key and block from
+pub fn self_test_random(seed: u64) -> Result<(), Error> {
+    let mut rng = SplitMix64(seed);
+
+    let mut key = [0u8; 10];
+    key[..8].copy_from_slice(&rng.next().to_be_bytes());
+    key[8..].copy_from_slice(&rng.next().to_be_bytes()[..2]);
+    let block = rng.next();
+
+    let ciphertext = encrypt_block(block, key);
+    if ciphertext == block
+        || Cipher::new(key).encrypt_block(block) != ciphertext
+        || decrypt_block(ciphertext, key) != block
+    {
+        return Err(Error::SelfTestFailed);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_test_random() {
+        for seed in [0, 1, 0x5ca1ab1e, u64::MAX].iter() {
+            assert_eq!(self_test_random(*seed), Ok(()));
+        }
+    }
+}
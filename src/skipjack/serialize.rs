@@ -0,0 +1,219 @@
+//! `serde` support for `Cipher`, which never serializes the key.
+//!
+//! A serialized `Cipher` is only a marker naming the algorithm: in JSON, it's
+//! `{"algorithm":"skipjack"}`. That lets a cipher sit inside a larger
+//! serializable configuration without the key ever being written out.
+//!
+//! Because the key isn't in the serialized form, `Cipher` can't implement
+//! `Deserialize` on its own. Instead, deserialize it with a `CipherSeed`, which
+//! carries the key in from elsewhere (an environment variable, a key file, a
+//! secrets manager, and so on).
+
+use core::borrow::Borrow;
+use core::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::Cipher;
+
+// The marker that a serialized `Cipher` carries in place of its key.
+const ALGORITHM: &str = "skipjack";
+
+// The fields of a serialized `Cipher`.
+const FIELDS: &[&str] = &["algorithm"];
+
+impl Serialize for Cipher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Cipher", FIELDS.len())?;
+        state.serialize_field("algorithm", ALGORITHM)?;
+        state.end()
+    }
+}
+
+/// Deserializes a `Cipher`, supplying the key that its serialized form omits.
+///
+/// Use this with `DeserializeSeed::deserialize`, or anywhere else `serde`
+/// accepts a seed (like `MapAccess::next_value_seed`). Deserialization fails
+/// if the marker names an algorithm other than Skipjack.
+pub struct CipherSeed {
+    cipher: Cipher,
+}
+
+impl CipherSeed {
+    /// Creates a seed that deserializes a `Cipher` with the given key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key for the deserialized cipher
+    pub fn new(key: impl Borrow<[u8; 10]>) -> Self {
+        CipherSeed {
+            cipher: Cipher::new(key),
+        }
+    }
+}
+
+impl fmt::Debug for CipherSeed {
+    // Never include the key (or anything derived from it) in debug output.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CipherSeed").finish_non_exhaustive()
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for CipherSeed {
+    type Value = Cipher;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Cipher, D::Error> {
+        deserializer.deserialize_struct(
+            "Cipher",
+            FIELDS,
+            CipherVisitor {
+                cipher: self.cipher,
+            },
+        )
+    }
+}
+
+// Visits the marker, and hands over the cipher once it's been checked.
+struct CipherVisitor {
+    cipher: Cipher,
+}
+
+impl<'de> Visitor<'de> for CipherVisitor {
+    type Value = Cipher;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a serialized Skipjack cipher")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Cipher, A::Error> {
+        let Algorithm = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+        Ok(self.cipher)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Cipher, A::Error> {
+        let mut algorithm = None;
+        while let Some(Field) = map.next_key()? {
+            if algorithm.is_some() {
+                return Err(de::Error::duplicate_field("algorithm"));
+            }
+            algorithm = Some(map.next_value::<Algorithm>()?);
+        }
+
+        match algorithm {
+            Some(Algorithm) => Ok(self.cipher),
+            None => Err(de::Error::missing_field("algorithm")),
+        }
+    }
+}
+
+// The only field of a serialized `Cipher`, `algorithm`.
+struct Field;
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("`algorithm`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                match value {
+                    "algorithm" => Ok(Field),
+                    _ => Err(de::Error::unknown_field(value, FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+// The value of the `algorithm` field, which must be `ALGORITHM`.
+struct Algorithm;
+
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AlgorithmVisitor;
+
+        impl Visitor<'_> for AlgorithmVisitor {
+            type Value = Algorithm;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "the string {:?}", ALGORITHM)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Algorithm, E> {
+                if value == ALGORITHM {
+                    Ok(Algorithm)
+                } else {
+                    Err(de::Error::invalid_value(de::Unexpected::Str(value), &self))
+                }
+            }
+        }
+
+        deserializer.deserialize_str(AlgorithmVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_serialize() {
+        let serialized = serde_json::to_vec(&Cipher::new(KEY)).unwrap();
+        assert_eq!(serialized, br#"{"algorithm":"skipjack"}"#);
+
+        // The serialized form is the same for every key, so it can't contain
+        // any of them.
+        let other = serde_json::to_vec(&Cipher::new([0xff; 10])).unwrap();
+        assert_eq!(serialized, other);
+        assert!(!serialized.windows(KEY.len()).any(|w| w == KEY));
+    }
+
+    #[test]
+    fn test_deserialize_seed() {
+        let serialized = serde_json::to_string(&Cipher::new(KEY)).unwrap();
+
+        let mut deserializer = serde_json::Deserializer::from_str(&serialized);
+        let cipher = CipherSeed::new(KEY).deserialize(&mut deserializer).unwrap();
+        assert_eq!(cipher.encrypt_block(0x33221100ddccbbaa), 0x2587cae27a12d300);
+
+        // A sequence works too, for formats that serialize structs that way.
+        let mut deserializer = serde_json::Deserializer::from_str(r#"["skipjack"]"#);
+        assert!(CipherSeed::new(KEY).deserialize(&mut deserializer).is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_rejects() {
+        let rejected = [
+            r#"{"algorithm":"aes"}"#,
+            r#"{}"#,
+            r#"{"algorithm":"skipjack","key":"00998877665544332211"}"#,
+            r#"{"algorithm":"skipjack","algorithm":"skipjack"}"#,
+            r#"[]"#,
+        ];
+
+        for input in rejected.iter() {
+            let mut deserializer = serde_json::Deserializer::from_str(input);
+            assert!(
+                CipherSeed::new(KEY).deserialize(&mut deserializer).is_err(),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(format!("{:?}", CipherSeed::new(KEY)), "CipherSeed { .. }");
+    }
+}
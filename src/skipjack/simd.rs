@@ -0,0 +1,131 @@
+//! Encryption of several blocks at once, structured for SIMD.
+//!
+//! Rather than using platform intrinsics (which would require `unsafe`), this
+//! module runs four independent encryptions in lockstep, with the state laid out
+//! "structure of arrays" style: each word of the state is an array of four lanes,
+//! one per block. Every step is then the same operation over four lanes, which
+//! the compiler is free to vectorize. The F table lookups remain per-lane loads.
+
+use super::schedule::RoundKeys;
+use super::{block_to_words, uses_rule_a, words_to_block, F};
+
+// One word of state for each of the four lanes.
+type Lanes = [u16; 4];
+
+// Rule G, applied to each lane. This is the same four round Feistel cipher as
+// `rule_g`, but with every byte replaced by four bytes (one per lane).
+fn rule_g4<K: RoundKeys>(words: Lanes, step: u16, key: &K) -> Lanes {
+    let round_key = key.round_key(step);
+
+    let mut high = [0u8; 4];
+    let mut low = [0u8; 4];
+    for lane in 0..4 {
+        high[lane] = (words[lane] >> 8) as u8;
+        low[lane] = words[lane] as u8;
+    }
+
+    // Each round transforms the low byte (and a byte of the key) into an index
+    // into F, then XORs with the high byte. The old low byte becomes the new
+    // high byte, and the result becomes the new low byte.
+    for &key_byte in round_key.iter() {
+        let mut next = [0u8; 4];
+        for lane in 0..4 {
+            next[lane] = F[(low[lane] ^ key_byte) as usize] ^ high[lane];
+        }
+
+        high = low;
+        low = next;
+    }
+
+    let mut result = [0u16; 4];
+    for lane in 0..4 {
+        result[lane] = (high[lane] as u16) << 8 | low[lane] as u16;
+    }
+
+    result
+}
+
+// Lane-wise XOR of two words, plus a constant.
+fn xor3(a: Lanes, b: Lanes, c: u16) -> Lanes {
+    let mut result = [0u16; 4];
+    for lane in 0..4 {
+        result[lane] = a[lane] ^ b[lane] ^ c;
+    }
+
+    result
+}
+
+/// Encrypts four 64-bit blocks with the given 80-bit secret key.
+///
+/// The result is identical to calling `encrypt_block` on each block.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to encrypt
+/// * `key` - The secret key to encrypt with
+pub fn encrypt_blocks4(blocks: [u64; 4], key: &[u8; 10]) -> [u64; 4] {
+    encrypt_blocks4_with(blocks, key)
+}
+
+// `encrypt_blocks4`, with the round keys from either the secret key or a
+// precomputed `KeySchedule`.
+pub(crate) fn encrypt_blocks4_with<K: RoundKeys>(blocks: [u64; 4], key: &K) -> [u64; 4] {
+    // Transpose the blocks into words-of-lanes: `words[i][lane]` is Word i + 1
+    // of block `lane`.
+    let mut words = [[0u16; 4]; 4];
+    for (lane, &block) in blocks.iter().enumerate() {
+        let block_words = block_to_words(block);
+        for i in 0..4 {
+            words[i][lane] = block_words[i];
+        }
+    }
+
+    // The same 32 rounds as `encrypt_block`, with each rule applied to every lane.
+    for counter in 1..=32u16 {
+        let [w1, w2, w3, w4] = words;
+        let g = rule_g4(w1, counter - 1, key);
+
+        words = if uses_rule_a(counter) {
+            [xor3(g, w4, counter), g, w2, w3]
+        } else {
+            [w4, g, xor3(w1, w2, counter), w3]
+        };
+    }
+
+    // Transpose back into blocks.
+    let mut result = [0u64; 4];
+    for (lane, block) in result.iter_mut().enumerate() {
+        *block = words_to_block([
+            words[0][lane],
+            words[1][lane],
+            words[2][lane],
+            words[3][lane],
+        ]);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encrypt_block;
+    use super::*;
+
+    #[test]
+    fn test_encrypt_blocks4() {
+        let key: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+        let blocks = [
+            0x33221100ddccbbaa,
+            0,
+            0xffffffffffffffff,
+            0x0123456789abcdef,
+        ];
+
+        let ciphertext = encrypt_blocks4(blocks, &key);
+
+        assert_eq!(ciphertext[0], 0x2587cae27a12d300);
+        for (c, &b) in ciphertext.iter().zip(blocks.iter()) {
+            assert_eq!(*c, encrypt_block(b, key));
+        }
+    }
+}
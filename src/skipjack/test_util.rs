@@ -0,0 +1,75 @@
+//! Conveniences for testing code that uses Skipjack.
+//!
+//! **For tests only.** This module is only available with the `test-util`
+//! feature, which should never be enabled outside of `[dev-dependencies]`.
+
+use core::borrow::Borrow;
+
+use super::Cipher;
+
+/// An endless, deterministic stream of pseudorandom blocks.
+///
+/// The `i`th block is the encryption of `start + i` (wrapping at `u64::MAX`)
+/// under the given key: like CTR mode's keystream, but as blocks rather than
+/// bytes. That makes a cheap source of data that looks random, and is the same
+/// on every run, for feeding stress tests and benchmarks.
+#[derive(Clone, Debug)]
+pub struct BlockStream {
+    cipher: Cipher,
+    counter: u64,
+}
+
+impl BlockStream {
+    /// Creates a new stream, starting from the encryption of `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The secret key to encrypt the counter with
+    /// * `start` - The first counter value
+    pub fn new(key: impl Borrow<[u8; 10]>, start: u64) -> Self {
+        BlockStream {
+            cipher: Cipher::new(key),
+            counter: start,
+        }
+    }
+}
+
+impl Iterator for BlockStream {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let block = self.cipher.encrypt_block(self.counter);
+        self.counter = self.counter.wrapping_add(1);
+
+        Some(block)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encrypt_block;
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_block_stream() {
+        let mut stream = BlockStream::new(KEY, 0x33221100ddccbbaa);
+        assert_eq!(stream.next(), Some(0x2587cae27a12d300));
+
+        for (i, block) in stream.take(16).enumerate() {
+            assert_eq!(block, encrypt_block(0x33221100ddccbbab + i as u64, KEY));
+        }
+
+        // The counter wraps around.
+        let blocks: Vec<u64> = BlockStream::new(KEY, u64::MAX).take(2).collect();
+        assert_eq!(
+            blocks,
+            [encrypt_block(u64::MAX, KEY), encrypt_block(0u64, KEY)]
+        );
+    }
+}
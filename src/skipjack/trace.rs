@@ -0,0 +1,78 @@
+//! Counting the operations that the cipher performs.
+//!
+//! With the `trace-ops` feature, every F table lookup and every XOR in the
+//! rules adds to a per-thread counter. Skipjack, as implemented here, has no
+//! branches that depend on the key or the data: the counter should therefore
+//! advance by exactly the same amount for every block and every key. The tests
+//! below check exactly that. (Which entries of F are read still depends on the
+//! data, and this says nothing about that; see `bitslice` for an implementation
+//! without lookups.)
+//!
+//! This is instrumentation for auditing, and slows the cipher down. Don't
+//! enable it in production builds.
+
+use std::cell::Cell;
+
+thread_local! {
+    static OP_COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+// Adds the given numbers of F lookups and XORs to this thread's count.
+pub(crate) fn record(lookups: u64, xors: u64) {
+    OP_COUNT.with(|count| count.set(count.get() + lookups + xors));
+}
+
+/// Returns the number of F lookups and XORs performed on this thread since it
+/// started (or since the last call to `reset_op_count`).
+pub fn op_count() -> u64 {
+    OP_COUNT.with(Cell::get)
+}
+
+/// Resets this thread's operation count to zero.
+pub fn reset_op_count() {
+    OP_COUNT.with(|count| count.set(0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decrypt_block, encrypt_block, encrypt_block_rounds};
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    // Returns the number of operations that `f` performs.
+    fn ops<T>(f: impl FnOnce() -> T) -> u64 {
+        reset_op_count();
+        let _ = f();
+        op_count()
+    }
+
+    #[test]
+    fn test_op_count_is_constant() {
+        let expected = ops(|| encrypt_block(0x33221100ddccbbaa, KEY));
+
+        // A rule B round is a G (4 lookups, 8 XORs) and 2 more XORs; a rule A
+        // round computes the same G twice.
+        assert_eq!(expected, 16 * 14 + 16 * 26);
+
+        let inputs = [
+            (0u64, [0u8; 10]),
+            (0xffffffffffffffff, [0xff; 10]),
+            (0x0123456789abcdef, *b"0123456789"),
+        ];
+        for &(block, key) in inputs.iter() {
+            assert_eq!(ops(|| encrypt_block(block, key)), expected);
+        }
+
+        let expected = ops(|| decrypt_block(0x2587cae27a12d300, KEY));
+        for &(block, key) in inputs.iter() {
+            assert_eq!(ops(|| decrypt_block(block, key)), expected);
+        }
+    }
+
+    #[test]
+    fn test_op_count_per_round() {
+        assert_eq!(ops(|| encrypt_block_rounds::<8>(0u64, KEY)), 8 * 26);
+        assert_eq!(ops(|| encrypt_block_rounds::<8>(!0u64, [0xff; 10])), 8 * 26);
+    }
+}
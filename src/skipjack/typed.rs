@@ -0,0 +1,287 @@
+//! Choosing a mode of operation at compile time.
+//!
+//! `apply` (with `legacy-modes`) takes the mode as a value, and decides what to
+//! do with it on every call. Here, the mode is part of the cipher's type
+//! instead: a `Cipher<Cbc>` always does CBC, and `Cipher::process` compiles
+//! down to exactly that mode, with nothing to dispatch on at runtime. This is
+//! for code that only ever uses one mode, and wants the compiler to know it.
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use skipjack::skipjack::typed::Ecb;
+//! use skipjack::skipjack::{Cipher, Direction};
+//!
+//! let key = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+//! let cipher: Cipher<Ecb> = Cipher::new(key);
+//!
+//! let ciphertext = cipher.process(Direction::Encrypt, b"8 bytes!", 0).unwrap();
+//! assert_eq!(cipher.process(Direction::Decrypt, &ciphertext, 0).unwrap(), b"8 bytes!");
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(feature = "legacy-modes")]
+use core::convert::TryInto;
+
+#[cfg(feature = "legacy-modes")]
+use super::cbc::{cbc_step_decrypt_with, cbc_step_encrypt_with};
+use super::{Block, Cipher, Direction, Error, BLOCK_SIZE};
+
+mod private {
+    // Prevents `TypedMode` from being implemented outside this crate.
+    pub trait Sealed {}
+
+    impl Sealed for super::Ecb {}
+    #[cfg(feature = "legacy-modes")]
+    impl Sealed for super::Cbc {}
+    #[cfg(feature = "legacy-modes")]
+    impl Sealed for super::Ctr {}
+}
+
+/// A mode of operation, as a type, for `Cipher<M>`.
+///
+/// This is implemented for `Ecb`, and (with `legacy-modes`) `Cbc` and `Ctr`,
+/// and can't be implemented outside of this crate.
+pub trait TypedMode: private::Sealed + Sized {
+    /// Encrypts or decrypts `data` in place with the given cipher, in this mode.
+    ///
+    /// This is what `Cipher::process_inplace` calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `cipher` - The cipher to encrypt or decrypt with
+    /// * `direction` - Whether to encrypt or decrypt
+    /// * `data` - The data to encrypt or decrypt, which is replaced by the result
+    /// * `iv` - The initialization vector (or, for CTR, the nonce)
+    fn apply(
+        cipher: &Cipher<Self>,
+        direction: Direction,
+        data: &mut [u8],
+        iv: u64,
+    ) -> Result<(), Error>;
+}
+
+/// Electronic codebook: each block is encrypted independently. The IV is ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ecb;
+
+/// Cipher block chaining (see `cbc`).
+#[cfg(feature = "legacy-modes")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cbc;
+
+/// Counter mode, with the IV as the nonce (see `ctr`).
+#[cfg(feature = "legacy-modes")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Ctr;
+
+impl TypedMode for Ecb {
+    fn apply(
+        cipher: &Cipher<Self>,
+        direction: Direction,
+        data: &mut [u8],
+        _iv: u64,
+    ) -> Result<(), Error> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(Error::BufferNotBlockAligned);
+        }
+
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block = Block::try_from(&*chunk)?;
+            let result = match direction {
+                Direction::Encrypt => cipher.encrypt_block(block),
+                Direction::Decrypt => cipher.decrypt_block(block),
+            };
+            chunk.copy_from_slice(&result.to_be_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "legacy-modes")]
+impl TypedMode for Cbc {
+    fn apply(
+        cipher: &Cipher<Self>,
+        direction: Direction,
+        data: &mut [u8],
+        iv: u64,
+    ) -> Result<(), Error> {
+        if !data.len().is_multiple_of(BLOCK_SIZE) {
+            return Err(Error::BufferNotBlockAligned);
+        }
+
+        let mut chain = iv.to_be_bytes();
+        for chunk in data.chunks_exact_mut(BLOCK_SIZE) {
+            let block: &mut [u8; BLOCK_SIZE] = chunk.try_into().unwrap();
+            match direction {
+                Direction::Encrypt => {
+                    cbc_step_encrypt_with(block, &mut chain, |b| cipher.encrypt_block(b))
+                }
+                Direction::Decrypt => {
+                    cbc_step_decrypt_with(block, &mut chain, |b| cipher.decrypt_block(b))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "legacy-modes")]
+impl TypedMode for Ctr {
+    fn apply(
+        cipher: &Cipher<Self>,
+        _direction: Direction,
+        data: &mut [u8],
+        iv: u64,
+    ) -> Result<(), Error> {
+        // CTR is its own inverse.
+        cipher.apply_ctr_keystream(data, iv);
+
+        Ok(())
+    }
+}
+
+impl<M: TypedMode> Cipher<M> {
+    /// Encrypts or decrypts `data` in place, in this cipher's mode.
+    ///
+    /// ECB and CBC operate on whole blocks, and don't pad: `data` must be a
+    /// multiple of the block size, or `Error::BufferNotBlockAligned` is
+    /// returned (and `data` is left untouched). CTR accepts data of any length.
+    /// Blocks are read big-endian, and the results are the same as `apply`'s.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - Whether to encrypt or decrypt
+    /// * `data` - The data to encrypt or decrypt, which is replaced by the result
+    /// * `iv` - The initialization vector (or, for CTR, the nonce)
+    pub fn process_inplace(
+        &self,
+        direction: Direction,
+        data: &mut [u8],
+        iv: u64,
+    ) -> Result<(), Error> {
+        M::apply(self, direction, data, iv)
+    }
+
+    /// Encrypts or decrypts `data` in this cipher's mode, returning the result.
+    ///
+    /// This is `process_inplace` on a copy of `data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - Whether to encrypt or decrypt
+    /// * `data` - The data to encrypt or decrypt
+    /// * `iv` - The initialization vector (or, for CTR, the nonce)
+    #[cfg(feature = "alloc")]
+    pub fn process(&self, direction: Direction, data: &[u8], iv: u64) -> Result<Vec<u8>, Error> {
+        let mut output = data.to_vec();
+        self.process_inplace(direction, &mut output, iv)?;
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+    const IV: u64 = 0x0123456789abcdef;
+
+    // Round-trips a few messages through the given cipher, returning the
+    // ciphertext of the last one.
+    #[cfg(feature = "alloc")]
+    fn roundtrip<M: TypedMode>(cipher: &Cipher<M>) -> Vec<u8> {
+        let mut ciphertext = Vec::new();
+        for plaintext in [&b""[..], b"8 bytes!", b"three whole blocks, 24 b"].iter() {
+            ciphertext = cipher.process(Direction::Encrypt, plaintext, IV).unwrap();
+            assert_eq!(ciphertext.len(), plaintext.len());
+
+            let decrypted = cipher.process(Direction::Decrypt, &ciphertext, IV).unwrap();
+            assert_eq!(&decrypted[..], *plaintext);
+        }
+
+        ciphertext
+    }
+
+    #[test]
+    fn test_ecb() {
+        let cipher: Cipher<Ecb> = Cipher::new(KEY);
+
+        let mut data = 0x33221100ddccbbaau64.to_be_bytes();
+        cipher
+            .process_inplace(Direction::Encrypt, &mut data, IV)
+            .unwrap();
+        assert_eq!(data, 0x2587cae27a12d300u64.to_be_bytes());
+
+        // The IV makes no difference.
+        let mut other = 0x33221100ddccbbaau64.to_be_bytes();
+        cipher
+            .process_inplace(Direction::Encrypt, &mut other, 0)
+            .unwrap();
+        assert_eq!(other, data);
+
+        let mut misaligned = [0u8; 7];
+        assert_eq!(
+            cipher.process_inplace(Direction::Encrypt, &mut misaligned, IV),
+            Err(Error::BufferNotBlockAligned)
+        );
+        assert_eq!(misaligned, [0u8; 7]);
+
+        #[cfg(feature = "alloc")]
+        roundtrip(&cipher);
+    }
+
+    #[test]
+    #[cfg(all(feature = "alloc", feature = "legacy-modes"))]
+    fn test_typed_modes() {
+        use super::super::{apply, Mode};
+
+        let ecb = roundtrip(&Cipher::new(KEY));
+        let cbc = roundtrip(&Cipher::new(KEY).with_mode::<Cbc>());
+        let ctr = roundtrip(&Cipher::new(KEY).with_mode::<Ctr>());
+
+        // Each typed cipher agrees with the runtime dispatcher for its mode.
+        let plaintext = b"three whole blocks, 24 b";
+        for (mode, ciphertext) in [(Mode::Ecb, ecb), (Mode::Cbc, cbc), (Mode::Ctr, ctr)].iter() {
+            assert_eq!(
+                &apply(*mode, Direction::Encrypt, plaintext, IV, KEY).unwrap(),
+                ciphertext,
+                "{:?}",
+                mode
+            );
+        }
+
+        // CTR takes data of any length; CBC doesn't.
+        let ctr = Cipher::new(KEY).with_mode::<Ctr>();
+        let ciphertext = ctr.process(Direction::Encrypt, b"odd", IV).unwrap();
+        assert_eq!(
+            ctr.process(Direction::Decrypt, &ciphertext, IV).unwrap(),
+            b"odd"
+        );
+        assert_eq!(
+            Cipher::new(KEY)
+                .with_mode::<Cbc>()
+                .process(Direction::Encrypt, b"odd", IV),
+            Err(Error::BufferNotBlockAligned)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "legacy-modes")]
+    fn test_with_mode() {
+        // Changing the mode keeps the key, and so does changing the key's mode.
+        let cbc = Cipher::new(KEY).with_mode::<Cbc>();
+        assert_eq!(cbc.encrypt_block(0x33221100ddccbbaa), 0x2587cae27a12d300);
+
+        let rekeyed: Cipher<Cbc> = cbc.with_key([0; 10]);
+        assert_eq!(
+            rekeyed.encrypt_block(0u64),
+            Cipher::new([0; 10]).encrypt_block(0u64)
+        );
+    }
+}
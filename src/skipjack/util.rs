@@ -0,0 +1,159 @@
+//! Miscellaneous helpers.
+
+use core::hint::black_box;
+
+use super::Error;
+
+/// Compares two byte slices for equality in constant time.
+///
+/// For slices of equal length, the running time depends only on that length,
+/// not on their contents or on where they first differ. Slices of different
+/// lengths are never equal, and are rejected immediately: lengths are not
+/// treated as secret.
+///
+/// Use this (rather than `==`) whenever comparing a computed authentication tag
+/// against a received one. Constant-time behavior is best-effort: we avoid data-
+/// dependent branches, but can't control everything the compiler does.
+///
+/// # Arguments
+///
+/// * `a` - The first slice to compare
+/// * `b` - The second slice to compare
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    // Accumulate the differences between every pair of bytes, rather than
+    // stopping at the first one. `black_box` discourages the optimizer from
+    // turning this back into an early exit.
+    let diff = a
+        .iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| black_box(acc | (x ^ y)));
+
+    diff == 0
+}
+
+// Parses a single hex digit, in either case.
+fn hex_digit(digit: u8) -> Result<u8, Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Error::InvalidHex),
+    }
+}
+
+// Parses a byte written as exactly two hex digits.
+fn hex_byte(digits: &[u8]) -> Result<u8, Error> {
+    match *digits {
+        [high, low] => Ok(hex_digit(high)? << 4 | hex_digit(low)?),
+        _ => Err(Error::InvalidHex),
+    }
+}
+
+// Parses each token as a byte written as two hex digits, and checks that there
+// are exactly `N` of them. Every token is checked before the count, so bad hex
+// is reported as such even when there's also the wrong amount of it.
+pub(crate) fn hex_bytes_fixed<'a, const N: usize>(
+    tokens: impl Iterator<Item = &'a [u8]>,
+) -> Result<[u8; N], Error> {
+    let mut bytes = [0u8; N];
+    let mut len = 0;
+
+    for token in tokens {
+        let byte = hex_byte(token)?;
+        if len < N {
+            bytes[len] = byte;
+        }
+        len += 1;
+    }
+
+    if len != N {
+        return Err(Error::InvalidHexLength);
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes a hex string into exactly `N` bytes, without allocating.
+///
+/// The string must consist only of hex digits (in either case), two per byte,
+/// with no prefix, separators, or whitespace. Returns `Error::InvalidHex` if
+/// it has an odd length or contains anything other than hex digits, or
+/// `Error::InvalidHexLength` if it doesn't decode to exactly `N` bytes.
+///
+/// # Arguments
+///
+/// * `s` - The hex string to decode
+pub fn hex_decode_fixed<const N: usize>(s: &str) -> Result<[u8; N], Error> {
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::InvalidHex);
+    }
+
+    hex_bytes_fixed(s.as_bytes().chunks(2))
+}
+
+// A small deterministic PRNG (SplitMix64), for when we need inputs that look
+// random but are reproducible. Not remotely suitable for keys or nonces.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(b"", b""));
+        assert!(ct_eq(b"skipjack", b"skipjack"));
+
+        assert!(!ct_eq(b"skipjack", b"skipjacj"));
+        assert!(!ct_eq(b"skipjack", b"rkipjack"));
+        assert!(!ct_eq(b"skipjack", b"skipjac"));
+    }
+
+    #[test]
+    fn test_hex_decode_fixed() {
+        assert_eq!(
+            hex_decode_fixed::<4>("0099aAfF"),
+            Ok([0x00, 0x99, 0xaa, 0xff])
+        );
+        assert_eq!(hex_decode_fixed::<0>(""), Ok([]));
+        assert_eq!(
+            hex_decode_fixed::<8>("33221100ddccbbaa"),
+            Ok(0x33221100ddccbbaau64.to_be_bytes())
+        );
+
+        // Odd lengths.
+        assert_eq!(hex_decode_fixed::<4>("0099aaf"), Err(Error::InvalidHex));
+        assert_eq!(hex_decode_fixed::<1>("0"), Err(Error::InvalidHex));
+
+        // Anything that isn't a hex digit.
+        assert_eq!(hex_decode_fixed::<4>("0099aafg"), Err(Error::InvalidHex));
+        assert_eq!(hex_decode_fixed::<4>("0x99aaff"), Err(Error::InvalidHex));
+        assert_eq!(hex_decode_fixed::<4>("00 99aaf"), Err(Error::InvalidHex));
+        assert_eq!(hex_decode_fixed::<2>("é1"), Err(Error::InvalidHex));
+
+        // The wrong number of bytes.
+        assert_eq!(
+            hex_decode_fixed::<4>("0099aa"),
+            Err(Error::InvalidHexLength)
+        );
+        assert_eq!(
+            hex_decode_fixed::<4>("0099aaff00"),
+            Err(Error::InvalidHexLength)
+        );
+        assert_eq!(hex_decode_fixed::<4>(""), Err(Error::InvalidHexLength));
+    }
+}
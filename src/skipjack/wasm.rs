@@ -0,0 +1,68 @@
+//! A minimal WebAssembly interface, with no JavaScript glue.
+//!
+//! The one export here takes and returns only integers, so a module built from
+//! it has no imports at all, and can be instantiated in any WebAssembly host.
+//! WebAssembly functions can take `i64`s, but many hosts make them awkward to
+//! pass, so the block and key come in as 32-bit halves.
+//!
+//! To build such a module, depend on this crate with `default-features = false`
+//! and `features = ["wasm-minimal"]` from a `cdylib` crate, and build that for
+//! `wasm32-unknown-unknown`.
+#![allow(unsafe_code)] // For `#[no_mangle]`: nothing here is otherwise unsafe.
+
+use super::encrypt_block;
+
+/// Encrypts a single 64-bit block, passed (along with the key) as 32-bit integers.
+///
+/// Everything is big-endian, as elsewhere in this crate:
+///
+/// * the block is `(hi << 32) | lo`;
+/// * the key's bytes are those of `k0`, then `k1`, then the low 16 bits of `k2`,
+///   each most significant byte first. That is, `key[0]` is `k0 >> 24` and
+///   `key[9]` is `k2 & 0xff`; the high 16 bits of `k2` are ignored.
+///
+/// The ciphertext is returned as a single `u64` (an `i64`, in WebAssembly terms).
+///
+/// # Arguments
+///
+/// * `hi` - The most significant 32 bits of the block
+/// * `lo` - The least significant 32 bits of the block
+/// * `k0` - Key bytes 0 to 3
+/// * `k1` - Key bytes 4 to 7
+/// * `k2` - Key bytes 8 and 9, in the low 16 bits
+#[no_mangle]
+pub extern "C" fn skipjack_encrypt_block_wasm(hi: u32, lo: u32, k0: u32, k1: u32, k2: u32) -> u64 {
+    let block = (u64::from(hi) << 32) | u64::from(lo);
+
+    let mut key = [0u8; 10];
+    key[..4].copy_from_slice(&k0.to_be_bytes());
+    key[4..8].copy_from_slice(&k1.to_be_bytes());
+    key[8..].copy_from_slice(&k2.to_be_bytes()[2..]);
+
+    encrypt_block(block, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skipjack_encrypt_block_wasm() {
+        // The key 00 99 88 77 66 55 44 33 22 11, split into 32-bit words.
+        assert_eq!(
+            skipjack_encrypt_block_wasm(0x33221100, 0xddccbbaa, 0x00998877, 0x66554433, 0x2211),
+            0x2587cae27a12d300
+        );
+
+        // The high half of `k2` doesn't matter.
+        assert_eq!(
+            skipjack_encrypt_block_wasm(0x33221100, 0xddccbbaa, 0x00998877, 0x66554433, 0xffff2211),
+            0x2587cae27a12d300
+        );
+
+        assert_eq!(
+            skipjack_encrypt_block_wasm(0, 1, 0, 0, 0),
+            encrypt_block(1u64, [0; 10])
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use core::ops::{Index, IndexMut};
+
+use super::{
+    block_to_words, check_counter, rule_a, rule_a_inv, rule_b, rule_b_inv, words_to_block,
+};
+
+/// The cipher's internal state: a 64-bit block as four 16-bit words.
+///
+/// Word 1 (index 0) is the high word of the block. The `apply_*` methods run
+/// a single round of the corresponding stepping rule on the words, which makes
+/// it possible to drive the cipher one round at a time.
+///
+/// Each rule takes the round counter, which it uses and then increments
+/// (for A and B) or decrements (for A' and B'). Encryption starts the
+/// counter at 1; decryption starts it at 32.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Words(pub [u16; 4]);
+
+impl Words {
+    /// Applies a single round of rule A.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - The round counter (1 to 32), which is incremented
+    /// * `key` - The secret key
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counter` is 0 or greater than 32.
+    pub fn apply_rule_a(&mut self, counter: &mut u16, key: &[u8; 10]) {
+        check_counter(*counter);
+        rule_a(&mut self.0, counter, key);
+    }
+
+    /// Applies a single round of rule B.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - The round counter (1 to 32), which is incremented
+    /// * `key` - The secret key
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counter` is 0 or greater than 32.
+    pub fn apply_rule_b(&mut self, counter: &mut u16, key: &[u8; 10]) {
+        check_counter(*counter);
+        rule_b(&mut self.0, counter, key);
+    }
+
+    /// Applies a single round of rule A', the inverse of rule A.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - The round counter (1 to 32), which is decremented
+    /// * `key` - The secret key
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counter` is 0 or greater than 32.
+    pub fn apply_rule_a_inv(&mut self, counter: &mut u16, key: &[u8; 10]) {
+        check_counter(*counter);
+        rule_a_inv(&mut self.0, counter, key);
+    }
+
+    /// Applies a single round of rule B', the inverse of rule B.
+    ///
+    /// # Arguments
+    ///
+    /// * `counter` - The round counter (1 to 32), which is decremented
+    /// * `key` - The secret key
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counter` is 0 or greater than 32.
+    pub fn apply_rule_b_inv(&mut self, counter: &mut u16, key: &[u8; 10]) {
+        check_counter(*counter);
+        rule_b_inv(&mut self.0, counter, key);
+    }
+}
+
+impl From<u64> for Words {
+    fn from(block: u64) -> Self {
+        Words(block_to_words(block))
+    }
+}
+
+impl From<Words> for u64 {
+    fn from(words: Words) -> Self {
+        words_to_block(words.0)
+    }
+}
+
+impl Index<usize> for Words {
+    type Output = u16;
+
+    fn index(&self, index: usize) -> &u16 {
+        &self.0[index]
+    }
+}
+
+impl IndexMut<usize> for Words {
+    fn index_mut(&mut self, index: usize) -> &mut u16 {
+        &mut self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+    #[test]
+    fn test_conversions() {
+        let words = Words::from(0x33221100ddccbbaa);
+
+        assert_eq!(words, Words([0x3322, 0x1100, 0xddcc, 0xbbaa]));
+        assert_eq!(words[0], 0x3322);
+        assert_eq!(words[3], 0xbbaa);
+        assert_eq!(u64::from(words), 0x33221100ddccbbaa);
+    }
+
+    #[test]
+    fn test_apply_rule_a() {
+        let mut words = Words::from(0x33221100ddccbbaa);
+        let mut counter = 1;
+        words.apply_rule_a(&mut counter, &KEY);
+
+        // This is the first round of the specification's worked example...
+        assert_eq!(words, Words([0xb004, 0x0baf, 0x1100, 0xddcc]));
+        assert_eq!(counter, 2);
+
+        // ...and matches the internal path.
+        let mut internal = block_to_words(0x33221100ddccbbaa);
+        rule_a(&mut internal, &mut 1, &KEY);
+        assert_eq!(words.0, internal);
+
+        // Rule A' undoes it.
+        counter -= 1;
+        words.apply_rule_a_inv(&mut counter, &KEY);
+        assert_eq!(u64::from(words), 0x33221100ddccbbaa);
+    }
+
+    #[test]
+    #[should_panic(expected = "round counter must be 1 to 32")]
+    fn test_apply_rule_zero_counter() {
+        Words::from(0u64).apply_rule_a_inv(&mut 0, &KEY);
+    }
+
+    #[test]
+    #[should_panic(expected = "round counter must be 1 to 32")]
+    fn test_apply_rule_counter_past_end() {
+        Words::from(0u64).apply_rule_b(&mut 33, &KEY);
+    }
+}
@@ -0,0 +1,77 @@
+// Statistical checks that Skipjack under a fixed key behaves as a permutation
+// on 64-bit blocks.
+//
+// Checking all 2^64 blocks is infeasible, so instead we encrypt a large set of
+// distinct blocks and check that no two of them collide, and that every one of
+// them decrypts back to where it started. A correct implementation can never
+// fail either check; one that accidentally isn't invertible for some inputs
+// (say, a rule that drops a word) collapses distinct blocks onto the same
+// ciphertext far more often than chance would.
+
+use std::collections::HashSet;
+
+use skipjack::skipjack::{decrypt_block, encrypt_block};
+
+mod common;
+
+use common::SplitMix64;
+
+const KEY: [u8; 10] = [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11];
+
+// Encrypts each of the given (distinct) blocks, asserting that no two
+// ciphertexts are equal and that every ciphertext decrypts to its plaintext.
+fn verify_bijection(blocks: &HashSet<u64>, key: [u8; 10]) {
+    let mut ciphertexts = HashSet::with_capacity(blocks.len());
+
+    for &block in blocks.iter() {
+        let ciphertext = encrypt_block(block, key);
+
+        assert!(
+            ciphertexts.insert(ciphertext),
+            "ciphertext collision: {:016x} for block {:016x}",
+            ciphertext,
+            block
+        );
+        assert_eq!(
+            decrypt_block(ciphertext, key),
+            block,
+            "round-trip mismatch for block {:016x}",
+            block
+        );
+    }
+}
+
+#[test]
+fn test_bijection_random_blocks() {
+    let mut rng = SplitMix64(0xb17ec7);
+
+    // Random plaintexts are themselves distinct with overwhelming probability,
+    // but collect them into a set anyway so the check can't be wrong.
+    let blocks: HashSet<u64> = (0..200_000).map(|_| rng.next()).collect();
+
+    verify_bijection(&blocks, KEY);
+}
+
+#[test]
+fn test_bijection_structured_blocks() {
+    // Low-entropy inputs are where a broken rule is most likely to show up:
+    // small counters (which only touch the last word) and single-bit blocks.
+    let mut blocks: HashSet<u64> = (0..65_536).collect();
+    blocks.extend((0..64).map(|bit| 1u64 << bit));
+    blocks.extend((0..64).map(|bit| !(1u64 << bit)));
+    blocks.extend((0..65_536u64).map(|i| i << 48));
+
+    verify_bijection(&blocks, KEY);
+}
+
+#[test]
+fn test_bijection_random_keys() {
+    let mut rng = SplitMix64(0x6b6579);
+
+    for _ in 0..16 {
+        let key = rng.key();
+
+        let blocks: HashSet<u64> = (0..10_000).map(|_| rng.next()).collect();
+        verify_bijection(&blocks, key);
+    }
+}
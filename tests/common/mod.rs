@@ -0,0 +1,26 @@
+// Helpers shared by the integration tests (and by `examples/gen_vectors.rs`).
+//
+// Each test file is its own crate, and pulls this in with `mod common;`, so a
+// helper that one of them doesn't use would otherwise warn as dead code.
+
+// A small deterministic PRNG (SplitMix64), so that failures are reproducible.
+pub struct SplitMix64(pub u64);
+
+impl SplitMix64 {
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // A key is the next 8 bytes, followed by the top 2 bytes of the one after.
+    #[allow(dead_code)]
+    pub fn key(&mut self) -> [u8; 10] {
+        let mut key = [0u8; 10];
+        key[..8].copy_from_slice(&self.next().to_be_bytes());
+        key[8..].copy_from_slice(&self.next().to_be_bytes()[..2]);
+        key
+    }
+}
@@ -0,0 +1,110 @@
+// Checks that the C header generated for the `ffi` feature compiles, and that C
+// code built against it gets the right answers.
+//
+// Cargo can't produce a static library from this crate without always doing so
+// (which would break `no_std` builds), so this test builds one itself: it runs
+// `rustc` on the crate with the `ffi` feature, then compiles `ffi/smoke.c` with
+// the platform's C compiler (as found by `cc`), links the two, and runs the result.
+#![cfg(all(feature = "ffi", unix))]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn rustc() -> Command {
+    Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+}
+
+fn check(output: Output, what: &str) -> Output {
+    assert!(
+        output.status.success(),
+        "{} failed:\n{}",
+        what,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
+
+// Returns the target triple that `rustc` builds for by default.
+fn host_triple() -> String {
+    let output = check(rustc().arg("-vV").output().unwrap(), "rustc -vV");
+    let version = String::from_utf8(output.stdout).unwrap();
+
+    version
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .expect("no host in rustc -vV")
+        .to_string()
+}
+
+// Builds the crate as a static library, and returns its path along with the
+// native libraries that anything linking it also has to link.
+fn build_staticlib(dir: &Path) -> (PathBuf, Vec<String>) {
+    let lib = dir.join("libskipjack.a");
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+
+    // The crate `include!`s generated code from `OUT_DIR`, so point it at ours.
+    let output = check(
+        rustc()
+            .env("OUT_DIR", env!("OUT_DIR"))
+            .args(["--edition", "2018", "--crate-name", "skipjack"])
+            .args(["--crate-type", "staticlib", "--cap-lints", "allow"])
+            .args(["--cfg", "feature=\"std\""])
+            .args(["--cfg", "feature=\"alloc\""])
+            .args(["--cfg", "feature=\"ffi\""])
+            .arg("--print=native-static-libs")
+            .arg("-o")
+            .arg(&lib)
+            .arg(Path::new(manifest_dir).join("src/lib.rs"))
+            .output()
+            .unwrap(),
+        "building the static library",
+    );
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let native_libs = stderr
+        .lines()
+        .find_map(|line| line.split("native-static-libs: ").nth(1))
+        .map(|libs| libs.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+
+    (lib, native_libs)
+}
+
+#[test]
+fn test_c_smoke() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    fs::create_dir_all(&dir).unwrap();
+
+    let (lib, native_libs) = build_staticlib(&dir);
+
+    let triple = host_triple();
+    let compiler = cc::Build::new()
+        .cargo_metadata(false)
+        .opt_level(0)
+        .target(&triple)
+        .host(&triple)
+        .get_compiler();
+
+    let smoke = dir.join("smoke");
+    check(
+        compiler
+            .to_command()
+            .args(["-std=c99", "-Wall", "-Werror"])
+            .arg("-I")
+            .arg(env!("OUT_DIR"))
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/ffi/smoke.c"))
+            .arg(&lib)
+            .args(&native_libs)
+            .arg("-o")
+            .arg(&smoke)
+            .output()
+            .unwrap(),
+        "compiling smoke.c",
+    );
+
+    let status = Command::new(&smoke).status().unwrap();
+    assert_eq!(status.code(), Some(0), "smoke.c failed a check");
+}
@@ -0,0 +1,31 @@
+/* A smoke test for the C interface, built against the generated skipjack.h.
+ * Exits with 0 if everything checks out, or the number of the failing check. */
+
+#include <stddef.h>
+
+#include "skipjack.h"
+
+int main(void) {
+    const skipjack_key key = {0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11};
+    uint64_t out = 0;
+
+    if (skipjack_encrypt_block(0x33221100ddccbbaaULL, key) != 0x2587cae27a12d300ULL) {
+        return 1;
+    }
+
+    if (skipjack_encrypt_block_checked(0x33221100ddccbbaaULL, &key, &out) != SKIPJACK_OK) {
+        return 2;
+    }
+    if (out != 0x2587cae27a12d300ULL) {
+        return 3;
+    }
+
+    if (skipjack_encrypt_block_checked(0x33221100ddccbbaaULL, NULL, &out) != SKIPJACK_ERR_NULL) {
+        return 4;
+    }
+    if (skipjack_encrypt_block_checked(0x33221100ddccbbaaULL, &key, NULL) != SKIPJACK_ERR_NULL) {
+        return 5;
+    }
+
+    return 0;
+}
@@ -0,0 +1,67 @@
+// Known-answer tests from published sources other than this crate.
+//
+// Matching vectors that someone else published is stronger evidence of
+// correctness than matching vectors we generated ourselves (see
+// `examples/gen_vectors.rs`), which only show that the output hasn't changed.
+//
+// At the moment, the only vector here is the worked example from the NIST
+// specification: no other library's Skipjack vectors could be sourced and
+// checked against an original when this file was written. (Crypto++ and older
+// releases of Botan both shipped Skipjack, and are the most likely places to
+// find more.) To add one, append it to `VECTORS` along with where it came from;
+// please only add vectors copied from a published source, never ones produced
+// by this crate.
+
+use skipjack::skipjack::{decrypt_block, encrypt_block, Block, Cipher, Key};
+
+// A published (key, plaintext, ciphertext) triple, as hex.
+struct Vector {
+    source: &'static str,
+    key: &'static str,
+    plaintext: &'static str,
+    ciphertext: &'static str,
+}
+
+const VECTORS: &[Vector] = &[Vector {
+    source: "NIST, SKIPJACK and KEA Algorithm Specifications (1998), worked example",
+    key: "00998877665544332211",
+    plaintext: "33221100ddccbbaa",
+    ciphertext: "2587cae27a12d300",
+}];
+
+#[test]
+fn test_published_vectors() {
+    for vector in VECTORS {
+        let key: Key = vector.key.parse().unwrap();
+        let plaintext: Block = vector.plaintext.parse().unwrap();
+        let ciphertext: Block = vector.ciphertext.parse().unwrap();
+        let (plaintext, ciphertext) = (u64::from(plaintext), u64::from(ciphertext));
+
+        assert_eq!(
+            encrypt_block(plaintext, key.0),
+            ciphertext,
+            "encryption: {}",
+            vector.source
+        );
+        assert_eq!(
+            decrypt_block(ciphertext, key.0),
+            plaintext,
+            "decryption: {}",
+            vector.source
+        );
+
+        let cipher = Cipher::new(key.0);
+        assert_eq!(
+            cipher.encrypt_block(plaintext),
+            ciphertext,
+            "{}",
+            vector.source
+        );
+        assert_eq!(
+            cipher.decrypt_block(ciphertext),
+            plaintext,
+            "{}",
+            vector.source
+        );
+    }
+}
@@ -0,0 +1,41 @@
+// A property test that every byte of the key affects the ciphertext.
+//
+// All ten key bytes feed into the key schedule, so changing any one of them
+// must change the ciphertext (barring a 2^-64 coincidence). A schedule bug
+// that drops a byte, such as a wrong index modulo 10, leaves some byte
+// unused: changing it then never changes anything, and this test catches it
+// on the first case.
+
+use proptest::prelude::*;
+
+use skipjack::skipjack::{encrypt_block, Cipher};
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1024))]
+
+    #[test]
+    fn test_every_key_byte_matters(key: [u8; 10], block: u64, flip in 1u8..=255) {
+        let ciphertext = encrypt_block(block, key);
+
+        for position in 0..key.len() {
+            let mut altered = key;
+            altered[position] ^= flip;
+
+            prop_assert_ne!(
+                encrypt_block(block, altered),
+                ciphertext,
+                "changing key byte {} didn't change the ciphertext",
+                position
+            );
+
+            // `Cipher` gets its key bytes from a precomputed schedule instead,
+            // so check that too.
+            prop_assert_ne!(
+                Cipher::new(altered).encrypt_block(block),
+                ciphertext,
+                "changing key byte {} didn't change Cipher's ciphertext",
+                position
+            );
+        }
+    }
+}
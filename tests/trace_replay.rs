@@ -0,0 +1,186 @@
+// Replays recorded round-by-round traces against `encrypt_block_trace`.
+//
+// An end-to-end vector only pins down the ciphertext: two mistakes that cancel
+// out (or one that the rest of the cipher happens to mask) can still produce it.
+// These traces pin down the state after every one of the 32 rounds, so an
+// off-by-one in any single round shows up at exactly that round.
+//
+// The traces were computed with a separate Python transcription of the
+// specification (sharing only the F table with this crate), not with this
+// crate. The first one is the specification's worked example: its first and
+// last states are the published plaintext and ciphertext, and it agrees with
+// the intermediate states that `test_block_rounds` quotes from the spec.
+
+use skipjack::skipjack::{encrypt_block, encrypt_block_trace, Words, ROUNDS};
+
+// A (block, key) pair and the state after each round of encrypting it, as
+// Words 1 through 4. Entry 0 is the plaintext; entry 32 is the ciphertext.
+struct Trace {
+    name: &'static str,
+    block: u64,
+    key: [u8; 10],
+    states: &'static [[u16; 4]; ROUNDS + 1],
+}
+
+const NIST_TRACE: [[u16; 4]; ROUNDS + 1] = [
+    [0x3322, 0x1100, 0xddcc, 0xbbaa],
+    [0xb004, 0x0baf, 0x1100, 0xddcc],
+    [0xe688, 0x3b46, 0x0baf, 0x1100],
+    [0x3c76, 0x2d75, 0x3b46, 0x0baf],
+    [0x4c45, 0x47ee, 0x2d75, 0x3b46],
+    [0xb949, 0x820a, 0x47ee, 0x2d75],
+    [0xf0e3, 0xdd90, 0x820a, 0x47ee],
+    [0xf9b9, 0xbe50, 0xdd90, 0x820a],
+    [0xd79b, 0x5599, 0xbe50, 0xdd90],
+    [0xdd90, 0x1e0b, 0x820b, 0xbe50],
+    [0xbe50, 0x4c52, 0xc391, 0x820b],
+    [0x820b, 0x7f51, 0xf209, 0xc391],
+    [0xc391, 0xf9c2, 0xfd56, 0xf209],
+    [0xf209, 0x25ff, 0x3a5e, 0xfd56],
+    [0xfd56, 0x65da, 0xd7f8, 0x3a5e],
+    [0x3a5e, 0x69d9, 0x9883, 0xd7f8],
+    [0xd7f8, 0x8990, 0x5397, 0x9883],
+    [0x9c00, 0x0492, 0x8990, 0x5397],
+    [0x9fdc, 0xcc59, 0x0492, 0x8990],
+    [0x3731, 0xbeb2, 0xcc59, 0x0492],
+    [0x7afb, 0x7e7d, 0xbeb2, 0xcc59],
+    [0x7759, 0xbb15, 0x7e7d, 0xbeb2],
+    [0xfb64, 0x45c0, 0xbb15, 0x7e7d],
+    [0x6f7f, 0x1115, 0x45c0, 0xbb15],
+    [0x65a7, 0xdeaa, 0x1115, 0x45c0],
+    [0x45c0, 0xe0f9, 0xbb14, 0x1115],
+    [0x1115, 0x3913, 0xa523, 0xbb14],
+    [0xbb14, 0x8ee6, 0x281d, 0xa523],
+    [0xa523, 0xbfe2, 0x35ee, 0x281d],
+    [0x281d, 0x0d84, 0x1adc, 0x35ee],
+    [0x35ee, 0xe6f1, 0x2587, 0x1adc],
+    [0x1adc, 0x60ee, 0xd300, 0x2587],
+    [0x2587, 0xcae2, 0x7a12, 0xd300],
+];
+
+const ZERO_TRACE: [[u16; 4]; ROUNDS + 1] = [
+    [0x0000, 0x0000, 0x0000, 0x0000],
+    [0x4a37, 0x4a36, 0x0000, 0x0000],
+    [0x4f08, 0x4f0a, 0x4a36, 0x0000],
+    [0x4d9f, 0x4d9c, 0x4f0a, 0x4a36],
+    [0x21cd, 0x6bff, 0x4d9c, 0x4f0a],
+    [0xd6cb, 0x99c4, 0x6bff, 0x4d9c],
+    [0x2cd4, 0x614e, 0x99c4, 0x6bff],
+    [0xec37, 0x87cf, 0x614e, 0x99c4],
+    [0x2112, 0xb8de, 0x87cf, 0x614e],
+    [0x614e, 0x7183, 0x99c5, 0x87cf],
+    [0x87cf, 0x9ba8, 0x10c7, 0x99c5],
+    [0x99c5, 0x1c87, 0x1c6c, 0x10c7],
+    [0x10c7, 0x3b19, 0x854e, 0x1c6c],
+    [0x1c6c, 0x02c6, 0x2bd3, 0x854e],
+    [0x854e, 0xf82d, 0x1ea4, 0x2bd3],
+    [0x2bd3, 0x73d8, 0x7d6c, 0x1ea4],
+    [0x1ea4, 0x8d4c, 0x581b, 0x7d6c],
+    [0x4857, 0x352a, 0x8d4c, 0x581b],
+    [0x0468, 0x5c61, 0x352a, 0x8d4c],
+    [0xdc38, 0x5167, 0x5c61, 0x352a],
+    [0x7b59, 0x4e67, 0x5167, 0x5c61],
+    [0xd759, 0x8b2d, 0x4e67, 0x5167],
+    [0x689e, 0x39ef, 0x8b2d, 0x4e67],
+    [0x3552, 0x7b22, 0x39ef, 0x8b2d],
+    [0x4e9a, 0xc5af, 0x7b22, 0x39ef],
+    [0x39ef, 0xc688, 0x8b2c, 0x7b22],
+    [0x7b22, 0x222b, 0xff7d, 0x8b2c],
+    [0x8b2c, 0x006a, 0x5912, 0xff7d],
+    [0xff7d, 0x9127, 0x8b5a, 0x5912],
+    [0x5912, 0xf3a2, 0x6e47, 0x8b5a],
+    [0x8b5a, 0x9f78, 0xaaae, 0x6e47],
+    [0x6e47, 0x0903, 0x143d, 0xaaae],
+    [0xaaae, 0x8ede, 0x6764, 0x143d],
+];
+
+const ASCII_TRACE: [[u16; 4]; ROUNDS + 1] = [
+    [0x0123, 0x4567, 0x89ab, 0xcdef],
+    [0xdd12, 0x10fc, 0x4567, 0x89ab],
+    [0xb7bd, 0x3e14, 0x10fc, 0x4567],
+    [0x0539, 0x405d, 0x3e14, 0x10fc],
+    [0x6ff4, 0x7f0c, 0x405d, 0x3e14],
+    [0x6068, 0x5e79, 0x7f0c, 0x405d],
+    [0x7833, 0x3868, 0x5e79, 0x7f0c],
+    [0xd908, 0xa603, 0x3868, 0x5e79],
+    [0xaf0f, 0xf17e, 0xa603, 0x3868],
+    [0x3868, 0x4eec, 0x5e78, 0xa603],
+    [0xa603, 0x8c0d, 0x768e, 0x5e78],
+    [0x5e78, 0x6240, 0x2a05, 0x768e],
+    [0x768e, 0xc166, 0x3c34, 0x2a05],
+    [0x2a05, 0xeaf0, 0xb7e5, 0x3c34],
+    [0x3c34, 0x033c, 0xc0fb, 0xb7e5],
+    [0xb7e5, 0x7dfc, 0x3f07, 0xc0fb],
+    [0xc0fb, 0xaf8e, 0xca09, 0x3f07],
+    [0xa3a7, 0x9cb1, 0xaf8e, 0xca09],
+    [0x94a0, 0x5ebb, 0x9cb1, 0xaf8e],
+    [0x6196, 0xce0b, 0x5ebb, 0x9cb1],
+    [0x8333, 0x1f96, 0xce0b, 0x5ebb],
+    [0x62e0, 0x3c4e, 0x1f96, 0xce0b],
+    [0xacbe, 0x62a3, 0x3c4e, 0x1f96],
+    [0x0b94, 0x1415, 0x62a3, 0x3c4e],
+    [0x663e, 0x5a68, 0x1415, 0x62a3],
+    [0x62a3, 0xfb0c, 0x3c4f, 0x1415],
+    [0x1415, 0xcbce, 0x99b5, 0x3c4f],
+    [0x3c4f, 0xeab9, 0xdfc0, 0x99b5],
+    [0x99b5, 0x7ba4, 0xd6ea, 0xdfc0],
+    [0xdfc0, 0xe1f6, 0xe20c, 0xd6ea],
+    [0xd6ea, 0x79f2, 0x3e28, 0xe20c],
+    [0xe20c, 0xb4a9, 0xaf07, 0x3e28],
+    [0x3e28, 0x4694, 0x5685, 0xaf07],
+];
+
+const TRACES: &[Trace] = &[
+    Trace {
+        name: "NIST worked example",
+        block: 0x33221100ddccbbaa,
+        key: [0x00, 0x99, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11],
+        states: &NIST_TRACE,
+    },
+    Trace {
+        name: "all zeroes",
+        block: 0,
+        key: [0; 10],
+        states: &ZERO_TRACE,
+    },
+    Trace {
+        name: "ASCII key",
+        block: 0x0123456789abcdef,
+        key: *b"0123456789",
+        states: &ASCII_TRACE,
+    },
+];
+
+#[test]
+fn test_trace_replay() {
+    for trace in TRACES {
+        let actual = encrypt_block_trace(trace.block, trace.key);
+
+        for (round, (actual, expected)) in actual.iter().zip(trace.states.iter()).enumerate() {
+            assert_eq!(
+                actual, expected,
+                "{}: state after round {} is {:04x?}, expected {:04x?}",
+                trace.name, round, actual, expected
+            );
+        }
+    }
+}
+
+#[test]
+fn test_trace_endpoints() {
+    // The recorded traces start at the plaintext and end at the ciphertext.
+    for trace in TRACES {
+        assert_eq!(
+            trace.states[0],
+            Words::from(trace.block).0,
+            "{}",
+            trace.name
+        );
+        assert_eq!(
+            u64::from(Words(trace.states[ROUNDS])),
+            encrypt_block(trace.block, trace.key),
+            "{}",
+            trace.name
+        );
+    }
+}